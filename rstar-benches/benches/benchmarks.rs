@@ -9,7 +9,7 @@ extern crate spade;
 use rand::{Rng, SeedableRng};
 use rand_hc::Hc128Rng;
 
-use rstar::{RStarInsertionStrategy, RTree, RTreeParams};
+use rstar::{RRStarInsertionStrategy, RStarInsertionStrategy, RTree, RTreeParams};
 
 use criterion::Criterion;
 
@@ -25,6 +25,15 @@ impl RTreeParams for Params {
     type DefaultInsertionStrategy = RStarInsertionStrategy;
 }
 
+struct RRStarParams;
+
+impl RTreeParams for RRStarParams {
+    const MIN_SIZE: usize = 2;
+    const MAX_SIZE: usize = 40;
+    const REINSERTION_COUNT: usize = 1;
+    type DefaultInsertionStrategy = RRStarInsertionStrategy;
+}
+
 const DEFAULT_BENCHMARK_TREE_SIZE: usize = 2000;
 
 fn bulk_load_baseline(c: &mut Criterion) {
@@ -88,6 +97,52 @@ fn tree_creation_quality(c: &mut Criterion) {
     });
 }
 
+fn insertion_strategy_comparison(c: &mut Criterion) {
+    const SIZE: usize = 100_000;
+    let points: Vec<_> = create_random_points(DEFAULT_BENCHMARK_TREE_SIZE, SEED_1);
+    let mut group = c.benchmark_group("insertion strategies");
+    group.bench_function("r-star insertion", |b| {
+        b.iter(|| {
+            let mut rtree = RTree::<_, Params>::new_with_params();
+            for point in &points {
+                rtree.insert(*point);
+            }
+        });
+    });
+    group.bench_function("rr-star insertion", |b| {
+        b.iter(|| {
+            let mut rtree = RTree::<_, RRStarParams>::new_with_params();
+            for point in &points {
+                rtree.insert(*point);
+            }
+        });
+    });
+
+    let points: Vec<_> = create_random_points(SIZE, SEED_1);
+    let mut r_star_tree = RTree::<_, Params>::new_with_params();
+    let mut rr_star_tree = RTree::<_, RRStarParams>::new_with_params();
+    for point in &points {
+        r_star_tree.insert(*point);
+        rr_star_tree.insert(*point);
+    }
+    let query_points = create_random_points(100, SEED_2);
+    group.bench_function("r-star quality", |b| {
+        b.iter(|| {
+            for query_point in &query_points {
+                r_star_tree.nearest_neighbor(query_point).unwrap();
+            }
+        });
+    });
+    group.bench_function("rr-star quality", |b| {
+        b.iter(|| {
+            for query_point in &query_points {
+                rr_star_tree.nearest_neighbor(query_point).unwrap();
+            }
+        });
+    });
+    group.finish();
+}
+
 fn locate_successful(c: &mut Criterion) {
     let points: Vec<_> = create_random_points(100_000, SEED_1);
     let query_point = points[500];
@@ -111,6 +166,7 @@ criterion_group!(
     bulk_load_baseline,
     bulk_load_comparison,
     tree_creation_quality,
+    insertion_strategy_comparison,
     locate_successful,
     locate_unsuccessful
 );
@@ -177,6 +177,14 @@ where
         self.lower.component_wise(&self.upper, |x, y| (x + y) / two)
     }
 
+    fn lower(&self) -> Self::Point {
+        self.lower
+    }
+
+    fn upper(&self) -> Self::Point {
+        self.upper
+    }
+
     fn intersection_area(&self, other: &Self) -> <Self::Point as Point>::Scalar {
         AABB {
             lower: self.lower.max_point(&other.lower),
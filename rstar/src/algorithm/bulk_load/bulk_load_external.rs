@@ -0,0 +1,956 @@
+use crate::aabb::AABB;
+use crate::algorithm::hilbert::hilbert_key;
+use crate::algorithm::selection_functions::{
+    SelectInEnvelopeFuncIntersecting, SelectInEnvelopeFunction, SelectionFunction,
+};
+use crate::envelope::Envelope;
+use crate::node::{ParentNode, RTreeNode};
+use crate::object::RTreeObject;
+use crate::params::{verify_config, RTreeConfig};
+use crate::point::{Point, PointExt};
+use crate::rtree::RTree;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::convert::TryFrom;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::bulk_load_hilbert::pack;
+use super::cluster_group_iterator::div_up;
+
+type PointOf<T> = <<T as RTreeObject>::Envelope as Envelope>::Point;
+
+/// The maximum number of sorted runs that are merged at once.
+const MAX_MERGE_WIDTH: usize = 64;
+
+/// An element that can be written to temporary files by an [ExternalBulkLoader].
+///
+/// This trait is implemented for arrays of `f32`, `f64`, `i32` and `i64` with up to nine
+/// dimensions.
+///
+/// # Example
+/// ```
+/// use rstar::{ExternalElement, RTreeObject, AABB};
+/// use std::io::{self, Read, Write};
+///
+/// struct Sensor {
+///     id: u32,
+///     position: [f64; 2],
+/// }
+///
+/// impl RTreeObject for Sensor {
+///     type Envelope = AABB<[f64; 2]>;
+///
+///     fn envelope(&self) -> Self::Envelope {
+///         AABB::from_point(self.position)
+///     }
+/// }
+///
+/// impl ExternalElement for Sensor {
+///     fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+///         writer.write_all(&self.id.to_le_bytes())?;
+///         self.position.write_to(writer)
+///     }
+///
+///     fn read_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+///         let mut id = [0; 4];
+///         reader.read_exact(&mut id)?;
+///         Ok(Sensor {
+///             id: u32::from_le_bytes(id),
+///             position: <[f64; 2]>::read_from(reader)?,
+///         })
+///     }
+/// }
+/// ```
+pub trait ExternalElement: RTreeObject + Sized {
+    /// Writes the element to a temporary file.
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+
+    /// Reads an element that has been written by [ExternalElement::write_to].
+    fn read_from<R: Read>(reader: &mut R) -> io::Result<Self>;
+}
+
+macro_rules! implement_external_element_for_arrays {
+    ($scalar:ty; $($size:expr),*) => {
+        $(
+            impl ExternalElement for [$scalar; $size] {
+                fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                    for coordinate in self {
+                        writer.write_all(&coordinate.to_le_bytes())?;
+                    }
+                    Ok(())
+                }
+
+                fn read_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+                    let mut result = [0 as $scalar; $size];
+                    let mut buffer = [0; ::std::mem::size_of::<$scalar>()];
+                    for coordinate in result.iter_mut() {
+                        reader.read_exact(&mut buffer)?;
+                        *coordinate = <$scalar>::from_le_bytes(buffer);
+                    }
+                    Ok(result)
+                }
+            }
+        )*
+    };
+}
+
+implement_external_element_for_arrays!(f32; 2, 3, 4, 5, 6, 7, 8, 9);
+implement_external_element_for_arrays!(f64; 2, 3, 4, 5, 6, 7, 8, 9);
+implement_external_element_for_arrays!(i32; 2, 3, 4, 5, 6, 7, 8, 9);
+implement_external_element_for_arrays!(i64; 2, 3, 4, 5, 6, 7, 8, 9);
+
+/// A temporary file that is removed when dropped.
+struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    fn create(directory: &Path) -> io::Result<(Self, BufWriter<File>)> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let path = directory.join(format!(
+            "rstar-bulk-load-{}-{}.tmp",
+            ::std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        Ok((TempFile { path }, BufWriter::new(file)))
+    }
+
+    fn open(&self) -> io::Result<BufReader<File>> {
+        Ok(BufReader::new(File::open(&self.path)?))
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// A temporary file containing elements sorted by their Hilbert values.
+struct Run {
+    file: TempFile,
+    len: usize,
+}
+
+impl Run {
+    fn write<T: ExternalElement>(directory: &Path, entries: Vec<(u64, T)>) -> io::Result<Self> {
+        let (file, mut writer) = TempFile::create(directory)?;
+        let len = entries.len();
+        for (key, element) in entries {
+            writer.write_all(&key.to_le_bytes())?;
+            element.write_to(&mut writer)?;
+        }
+        writer.flush()?;
+        Ok(Run { file, len })
+    }
+
+    fn merge<T: ExternalElement>(directory: &Path, runs: &[Run]) -> io::Result<Self> {
+        let (file, mut writer) = TempFile::create(directory)?;
+        let mut len = 0;
+        merge_runs::<T, _>(runs, |key, element| {
+            len += 1;
+            writer.write_all(&key.to_le_bytes())?;
+            element.write_to(&mut writer)
+        })?;
+        writer.flush()?;
+        Ok(Run { file, len })
+    }
+}
+
+struct RunReader {
+    reader: BufReader<File>,
+    remaining: usize,
+}
+
+impl RunReader {
+    fn next<T: ExternalElement>(&mut self) -> io::Result<Option<(u64, T)>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        let mut key = [0; 8];
+        self.reader.read_exact(&mut key)?;
+        let element = T::read_from(&mut self.reader)?;
+        Ok(Some((u64::from_le_bytes(key), element)))
+    }
+}
+
+/// Merges sorted runs and passes their elements to `sink` in the order of their keys.
+///
+/// Elements with equal keys are passed in the order of their runs.
+fn merge_runs<T, F>(runs: &[Run], mut sink: F) -> io::Result<()>
+where
+    T: ExternalElement,
+    F: FnMut(u64, T) -> io::Result<()>,
+{
+    let mut readers = Vec::with_capacity(runs.len());
+    for run in runs {
+        readers.push(RunReader {
+            reader: run.file.open()?,
+            remaining: run.len,
+        });
+    }
+    let mut heads = Vec::with_capacity(runs.len());
+    let mut heap = BinaryHeap::with_capacity(runs.len());
+    for (index, reader) in readers.iter_mut().enumerate() {
+        let head = reader.next::<T>()?.map(|(key, element)| {
+            heap.push(Reverse((key, index)));
+            element
+        });
+        heads.push(head);
+    }
+    while let Some(Reverse((key, index))) = heap.pop() {
+        let element = heads[index].take().unwrap();
+        if let Some((next_key, next)) = readers[index].next()? {
+            heads[index] = Some(next);
+            heap.push(Reverse((next_key, index)));
+        }
+        sink(key, element)?;
+    }
+    Ok(())
+}
+
+/// Sorts elements by their Hilbert values using temporary files under a memory budget.
+///
+/// [RTree::bulk_load] requires all elements to be collected into a single `Vec`, which is
+/// partitioned in memory. This loader instead accepts an iterator and sorts the elements by the
+/// Hilbert value of their envelope's center using temporary files. At most `memory_budget`
+/// bytes of elements are sorted in memory at once. The sorted elements are then packed like
+/// [BulkLoadAlgorithm::Hilbert](crate::BulkLoadAlgorithm::Hilbert) does, either into an
+/// in-memory [RTree] or into a file which is queried with an [ExternalRTree].
+///
+/// Sorting requires the bounding box of all centers. [ExternalBulkLoader::sort] determines it
+/// by storing the input in a temporary file first. [ExternalBulkLoader::sort_within] accepts
+/// known bounds instead and sorts the input in a single pass. While sorting, the temporary files
+/// require up to three times the serialized size of all elements on disk.
+///
+/// # Example
+/// ```
+/// use rstar::ExternalBulkLoader;
+///
+/// let points = (0..10_000).map(|i| [(i % 100) as f64, (i / 100) as f64]);
+/// // Sort at most 64 KiB of elements at once
+/// let tree = ExternalBulkLoader::new(64 * 1024).load(points).unwrap();
+/// assert_eq!(tree.size(), 10_000);
+/// assert!(tree.contains(&[42.0, 17.0]));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ExternalBulkLoader {
+    memory_budget: usize,
+    temp_dir: PathBuf,
+    config: RTreeConfig,
+}
+
+impl ExternalBulkLoader {
+    /// Creates a new loader that sorts at most `memory_budget` bytes of elements in memory.
+    ///
+    /// Only the in-memory size of an element (`std::mem::size_of`) and its Hilbert value are
+    /// taken into account. Temporary files are created in the directory returned by
+    /// `std::env::temp_dir`.
+    pub fn new(memory_budget: usize) -> Self {
+        ExternalBulkLoader {
+            memory_budget,
+            temp_dir: ::std::env::temp_dir(),
+            config: RTreeConfig::default(),
+        }
+    }
+
+    /// Sets the directory for temporary files.
+    pub fn temp_dir<P: Into<PathBuf>>(mut self, temp_dir: P) -> Self {
+        self.temp_dir = temp_dir.into();
+        self
+    }
+
+    /// Sets the configuration of the resulting tree.
+    ///
+    /// Only `max_size` and `bulk_load_fill_factor` affect bulk loading, see [RTreeConfig].
+    pub fn config(mut self, config: RTreeConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Loads the given elements into a new in-memory tree.
+    ///
+    /// Only the sorting is performed in external memory, the resulting tree must fit into
+    /// memory. Use [SortedElements::write_to_file] for trees that do not.
+    ///
+    /// Returns an error if a temporary file can not be written or read.
+    ///
+    /// # Panics
+    /// Panics if the configuration is invalid, see [RTree::with_config].
+    pub fn load<T, I>(&self, elements: I) -> io::Result<RTree<T>>
+    where
+        T: ExternalElement,
+        I: IntoIterator<Item = T>,
+    {
+        self.sort(elements)?.into_rtree()
+    }
+
+    /// Sorts the given elements by the Hilbert values of their envelope's centers.
+    ///
+    /// The input is stored in a temporary file to determine the bounding box of all centers
+    /// before sorting. Use [ExternalBulkLoader::sort_within] if the bounds are known in advance.
+    ///
+    /// Returns an error if a temporary file can not be written or read.
+    ///
+    /// # Panics
+    /// Panics if the configuration is invalid, see [RTree::with_config].
+    pub fn sort<T, I>(&self, elements: I) -> io::Result<SortedElements<T>>
+    where
+        T: ExternalElement,
+        I: IntoIterator<Item = T>,
+    {
+        verify_config::<T>(&self.config);
+        let (input, mut writer) = TempFile::create(&self.temp_dir)?;
+        let mut size = 0;
+        let mut frame: Option<(PointOf<T>, PointOf<T>)> = None;
+        for element in elements {
+            let center = element.envelope().center();
+            frame = Some(match frame {
+                None => (center, center),
+                Some((lower, upper)) => (lower.min_point(&center), upper.max_point(&center)),
+            });
+            element.write_to(&mut writer)?;
+            size += 1;
+        }
+        writer.flush()?;
+        drop(writer);
+        let (lower, upper) = match frame {
+            Some(frame) => frame,
+            None => return Ok(self.sorted(Vec::new(), 0)),
+        };
+        let mut reader = input.open()?;
+        let elements = (0..size).map(|_| T::read_from(&mut reader));
+        self.sort_in_frame(elements, &lower, &upper)
+    }
+
+    /// Sorts the given elements in a single pass, using known bounds of their centers.
+    ///
+    /// `bounds` should contain the centers of all element envelopes. Elements outside of it are
+    /// still sorted and loaded correctly, but their order becomes less favorable for queries.
+    ///
+    /// Returns an error if a temporary file can not be written or read.
+    ///
+    /// # Panics
+    /// Panics if the configuration is invalid, see [RTree::with_config].
+    pub fn sort_within<T, I>(
+        &self,
+        elements: I,
+        bounds: &AABB<PointOf<T>>,
+    ) -> io::Result<SortedElements<T>>
+    where
+        T: ExternalElement,
+        I: IntoIterator<Item = T>,
+    {
+        verify_config::<T>(&self.config);
+        let elements = elements.into_iter().map(Ok);
+        self.sort_in_frame(elements, &bounds.lower(), &bounds.upper())
+    }
+
+    fn sort_in_frame<T, I>(
+        &self,
+        mut elements: I,
+        lower: &PointOf<T>,
+        upper: &PointOf<T>,
+    ) -> io::Result<SortedElements<T>>
+    where
+        T: ExternalElement,
+        I: Iterator<Item = io::Result<T>>,
+    {
+        // Sort chunks of elements that fit into the memory budget. The Hilbert values are
+        // calculated while reading, no further memory is required per element.
+        let run_length = (self.memory_budget / ::std::mem::size_of::<(u64, T)>().max(1)).max(1);
+        let mut coordinates = vec![0u64; PointOf::<T>::DIMENSIONS];
+        let mut runs = Vec::new();
+        let mut size = 0;
+        loop {
+            let mut entries = Vec::with_capacity(run_length);
+            for element in elements.by_ref().take(run_length) {
+                let element = element?;
+                let center = element.envelope().center();
+                let key = hilbert_key(&center, lower, upper, &mut coordinates);
+                entries.push((key, element));
+            }
+            if entries.is_empty() {
+                break;
+            }
+            size += entries.len();
+            entries.sort_by_key(|(key, _)| *key);
+            runs.push(Run::write(&self.temp_dir, entries)?);
+        }
+
+        // Limit the number of files that are read at once
+        while runs.len() > MAX_MERGE_WIDTH {
+            let mut merged = Vec::with_capacity(div_up(runs.len(), MAX_MERGE_WIDTH));
+            for group in runs.chunks(MAX_MERGE_WIDTH) {
+                merged.push(Run::merge::<T>(&self.temp_dir, group)?);
+            }
+            runs = merged;
+        }
+        Ok(self.sorted(runs, size))
+    }
+
+    fn sorted<T>(&self, runs: Vec<Run>, size: usize) -> SortedElements<T> {
+        SortedElements {
+            runs,
+            size,
+            temp_dir: self.temp_dir.clone(),
+            config: self.config,
+            element: PhantomData,
+        }
+    }
+}
+
+/// Elements that have been sorted into temporary files by an [ExternalBulkLoader].
+///
+/// The temporary files are removed when this is dropped.
+pub struct SortedElements<T> {
+    runs: Vec<Run>,
+    size: usize,
+    temp_dir: PathBuf,
+    config: RTreeConfig,
+    element: PhantomData<fn() -> T>,
+}
+
+impl<T> SortedElements<T>
+where
+    T: ExternalElement,
+{
+    /// Returns the number of sorted elements.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Packs the sorted elements into a new in-memory tree.
+    ///
+    /// Returns an error if a temporary file can not be read.
+    pub fn into_rtree(self) -> io::Result<RTree<T>> {
+        let max_size = self.config.bulk_load_node_size();
+        let mut nodes = Vec::with_capacity(div_up(self.size, max_size));
+        let mut leaves = Vec::with_capacity(max_size);
+        merge_runs(&self.runs, |_, element| {
+            leaves.push(RTreeNode::Leaf(element));
+            if leaves.len() == max_size {
+                let children = ::std::mem::replace(&mut leaves, Vec::with_capacity(max_size));
+                nodes.push(RTreeNode::Parent(ParentNode::new_parent(children)));
+            }
+            Ok(())
+        })?;
+        if !leaves.is_empty() {
+            nodes.push(RTreeNode::Parent(ParentNode::new_parent(leaves)));
+        }
+        let root = match nodes.pop() {
+            None => return Ok(RTree::with_config(self.config)),
+            Some(RTreeNode::Parent(root)) if nodes.is_empty() => root,
+            last => {
+                nodes.extend(last);
+                while nodes.len() > max_size {
+                    nodes = pack(nodes, max_size);
+                }
+                ParentNode::new_parent(nodes)
+            }
+        };
+        Ok(RTree::new_from_root(root, self.size, self.config))
+    }
+
+    /// Packs the sorted elements into a tree that is written to a file.
+    ///
+    /// The file is created or truncated. The tree is written level by level, starting with the
+    /// leaves. Only a single node of each level is held in memory, all other nodes are stored
+    /// in the file or in temporary files. The resulting tree is identical to the one returned
+    /// by [SortedElements::into_rtree].
+    ///
+    /// Returns an error if a file can not be written or read.
+    pub fn write_to_file<P, Q>(self, path: Q) -> io::Result<ExternalRTree<T>>
+    where
+        T: RTreeObject<Envelope = AABB<P>>,
+        P: Point + ExternalElement,
+        Q: Into<PathBuf>,
+    {
+        let path = path.into();
+        let mut output = PositionWriter {
+            writer: BufWriter::new(File::create(&path)?),
+            position: 0,
+        };
+        output.write_all(MAGIC)?;
+        let max_size = self.config.bulk_load_node_size();
+
+        let mut elements = Vec::<T>::with_capacity(max_size.min(self.size));
+        if self.size <= max_size {
+            merge_runs(&self.runs, |_, element| {
+                elements.push(element);
+                Ok(())
+            })?;
+            let (_, root) = write_leaf_node(&mut output, &elements)?;
+            return self.finish(output, root, path);
+        }
+
+        // Write the leaf nodes while storing their envelopes and offsets in a temporary file
+        let mut level = Level::create(&self.temp_dir)?;
+        merge_runs(&self.runs, |_, element| {
+            elements.push(element);
+            if elements.len() == max_size {
+                level.push(write_leaf_node(&mut output, &elements)?)?;
+                elements.clear();
+            }
+            Ok(())
+        })?;
+        if !elements.is_empty() {
+            level.push(write_leaf_node(&mut output, &elements)?)?;
+        }
+        drop(elements);
+
+        // Pack each level into the next one until it fits into the root
+        let mut children = Vec::<(AABB<P>, u64)>::with_capacity(max_size);
+        loop {
+            let (file, len) = level.finish()?;
+            let mut reader = file.open()?;
+            if len <= max_size {
+                for _ in 0..len {
+                    children.push(read_entry(&mut reader)?);
+                }
+                let (_, root) = write_parent_node(&mut output, &children)?;
+                return self.finish(output, root, path);
+            }
+            level = Level::create(&self.temp_dir)?;
+            for _ in 0..len {
+                children.push(read_entry(&mut reader)?);
+                if children.len() == max_size {
+                    level.push(write_parent_node(&mut output, &children)?)?;
+                    children.clear();
+                }
+            }
+            if !children.is_empty() {
+                level.push(write_parent_node(&mut output, &children)?)?;
+                children.clear();
+            }
+        }
+    }
+
+    fn finish<W: Write>(
+        &self,
+        mut output: PositionWriter<W>,
+        root: u64,
+        path: PathBuf,
+    ) -> io::Result<ExternalRTree<T>> {
+        output.write_all(&root.to_le_bytes())?;
+        output.write_all(&(self.size as u64).to_le_bytes())?;
+        output.write_all(MAGIC)?;
+        output.flush()?;
+        Ok(ExternalRTree {
+            path,
+            root,
+            size: self.size,
+            element: PhantomData,
+        })
+    }
+}
+
+/// Identifies files written by [SortedElements::write_to_file]. It is stored at the start and
+/// at the end of each file.
+const MAGIC: &[u8; 8] = b"RSTARPK1";
+
+const LEAF_NODE: u8 = 0;
+const PARENT_NODE: u8 = 1;
+
+/// Keeps track of the number of bytes written so far, which is the offset of the next node.
+struct PositionWriter<W> {
+    writer: W,
+    position: u64,
+}
+
+impl<W: Write> Write for PositionWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.writer.write(buf)?;
+        self.position += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// The envelopes and offsets of the nodes of a single level, stored in a temporary file.
+struct Level {
+    file: TempFile,
+    writer: BufWriter<File>,
+    len: usize,
+}
+
+impl Level {
+    fn create(directory: &Path) -> io::Result<Self> {
+        let (file, writer) = TempFile::create(directory)?;
+        Ok(Level {
+            file,
+            writer,
+            len: 0,
+        })
+    }
+
+    fn push<P>(&mut self, (envelope, offset): (AABB<P>, u64)) -> io::Result<()>
+    where
+        P: Point + ExternalElement,
+    {
+        write_envelope(&envelope, &mut self.writer)?;
+        self.writer.write_all(&offset.to_le_bytes())?;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> io::Result<(TempFile, usize)> {
+        self.writer.flush()?;
+        Ok((self.file, self.len))
+    }
+}
+
+fn write_leaf_node<T, W>(
+    output: &mut PositionWriter<W>,
+    elements: &[T],
+) -> io::Result<(T::Envelope, u64)>
+where
+    T: ExternalElement,
+    W: Write,
+{
+    let offset = output.position;
+    let mut envelope = T::Envelope::new_empty();
+    output.write_all(&[LEAF_NODE])?;
+    output.write_all(&(elements.len() as u64).to_le_bytes())?;
+    for element in elements {
+        envelope.merge(&element.envelope());
+        element.write_to(output)?;
+    }
+    Ok((envelope, offset))
+}
+
+fn write_parent_node<P, W>(
+    output: &mut PositionWriter<W>,
+    children: &[(AABB<P>, u64)],
+) -> io::Result<(AABB<P>, u64)>
+where
+    P: Point + ExternalElement,
+    W: Write,
+{
+    let offset = output.position;
+    let mut envelope = AABB::new_empty();
+    output.write_all(&[PARENT_NODE])?;
+    output.write_all(&(children.len() as u64).to_le_bytes())?;
+    for (child_envelope, child_offset) in children {
+        envelope.merge(child_envelope);
+        write_envelope(child_envelope, output)?;
+        output.write_all(&child_offset.to_le_bytes())?;
+    }
+    Ok((envelope, offset))
+}
+
+fn read_entry<P, R>(reader: &mut R) -> io::Result<(AABB<P>, u64)>
+where
+    P: Point + ExternalElement,
+    R: Read,
+{
+    let envelope = read_envelope(reader)?;
+    Ok((envelope, read_u64(reader)?))
+}
+
+fn write_envelope<P, W>(envelope: &AABB<P>, writer: &mut W) -> io::Result<()>
+where
+    P: Point + ExternalElement,
+    W: Write,
+{
+    envelope.lower().write_to(writer)?;
+    envelope.upper().write_to(writer)
+}
+
+fn read_envelope<P, R>(reader: &mut R) -> io::Result<AABB<P>>
+where
+    P: Point + ExternalElement,
+    R: Read,
+{
+    let lower = P::read_from(reader)?;
+    let upper = P::read_from(reader)?;
+    Ok(AABB::from_corners(lower, upper))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut buffer = [0; 8];
+    reader.read_exact(&mut buffer)?;
+    Ok(u64::from_le_bytes(buffer))
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// A packed r-tree that is stored in a file and queried without loading it into memory.
+///
+/// The file is written by [SortedElements::write_to_file]. Each query reads only the nodes it
+/// needs to visit. The envelopes of the nodes are stored as well, the elements must thus use an
+/// [AABB] of points implementing [ExternalElement] as their envelope, e.g. of arrays.
+///
+/// # Example
+/// ```
+/// use rstar::{ExternalBulkLoader, ExternalRTree, AABB};
+///
+/// let path = std::env::temp_dir().join("rstar-external-rtree-example.bin");
+/// let points = (0..10_000).map(|i| [(i % 100) as f64, (i / 100) as f64]);
+/// let bounds = AABB::from_corners([0.0, 0.0], [99.0, 99.0]);
+/// let tree = ExternalBulkLoader::new(64 * 1024)
+///     .sort_within(points, &bounds)
+///     .unwrap()
+///     .write_to_file(&path)
+///     .unwrap();
+/// assert_eq!(tree.size(), 10_000);
+///
+/// // The file can be opened again later
+/// let tree: ExternalRTree<[f64; 2]> = ExternalRTree::open(&path).unwrap();
+/// let query = AABB::from_corners([10.0, 10.0], [12.0, 11.0]);
+/// assert_eq!(tree.locate_in_envelope(&query).unwrap().len(), 6);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct ExternalRTree<T> {
+    path: PathBuf,
+    root: u64,
+    size: usize,
+    element: PhantomData<fn() -> T>,
+}
+
+impl<T, P> ExternalRTree<T>
+where
+    T: ExternalElement + RTreeObject<Envelope = AABB<P>>,
+    P: Point + ExternalElement,
+{
+    /// Opens a tree that has been written by [SortedElements::write_to_file].
+    ///
+    /// Returns an error if the file can not be read or has not been written by rstar. The
+    /// element type is not checked, it must match the type the file has been written with.
+    pub fn open<Q: Into<PathBuf>>(path: Q) -> io::Result<Self> {
+        let path = path.into();
+        let mut file = File::open(&path)?;
+        let mut magic = [0; 8];
+        file.read_exact(&mut magic)?;
+        if &magic != MAGIC || file.metadata()?.len() < 32 {
+            return Err(invalid_data("Not a packed r-tree file"));
+        }
+        file.seek(SeekFrom::End(-24))?;
+        let root = read_u64(&mut file)?;
+        let size = read_u64(&mut file)?;
+        file.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("Packed r-tree file is incomplete"));
+        }
+        let size = usize::try_from(size).map_err(|_| invalid_data("Packed r-tree is too large"))?;
+        Ok(ExternalRTree {
+            path,
+            root,
+            size,
+            element: PhantomData,
+        })
+    }
+
+    /// Returns the path of the tree's file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the number of elements contained in the tree.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns all elements contained in an [Envelope].
+    ///
+    /// See [RTree::locate_in_envelope].
+    pub fn locate_in_envelope(&self, envelope: &T::Envelope) -> io::Result<Vec<T>> {
+        self.locate_with_selection_function(SelectInEnvelopeFunction::new(*envelope))
+    }
+
+    /// Returns all elements whose envelope intersects a given [Envelope].
+    ///
+    /// See [RTree::locate_in_envelope_intersecting].
+    pub fn locate_in_envelope_intersecting(&self, envelope: &T::Envelope) -> io::Result<Vec<T>> {
+        self.locate_with_selection_function(SelectInEnvelopeFuncIntersecting::new(*envelope))
+    }
+
+    /// Returns all elements that are selected by a [SelectionFunction].
+    ///
+    /// Returns an error if the file can not be read.
+    pub fn locate_with_selection_function<S>(&self, function: S) -> io::Result<Vec<T>>
+    where
+        S: SelectionFunction<T>,
+    {
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let mut result = Vec::new();
+        let mut offsets = vec![self.root];
+        while let Some(offset) = offsets.pop() {
+            reader.seek(SeekFrom::Start(offset))?;
+            let mut kind = [0];
+            reader.read_exact(&mut kind)?;
+            let len = read_u64(&mut reader)?;
+            match kind[0] {
+                LEAF_NODE => {
+                    for _ in 0..len {
+                        let element = T::read_from(&mut reader)?;
+                        if function.should_unpack_leaf(&element) {
+                            result.push(element);
+                        }
+                    }
+                }
+                PARENT_NODE => {
+                    for _ in 0..len {
+                        let (envelope, child) = read_entry(&mut reader)?;
+                        if function.should_unpack_parent(&envelope) {
+                            offsets.push(child);
+                        }
+                    }
+                }
+                _ => return Err(invalid_data("Invalid node in packed r-tree file")),
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ExternalBulkLoader, ExternalElement, ExternalRTree};
+    use crate::aabb::AABB;
+    use crate::algorithm::bulk_load::BulkLoadAlgorithm;
+    use crate::params::RTreeConfig;
+    use crate::test_utilities::*;
+    use crate::RTree;
+    use std::fs;
+    use std::io::Cursor;
+    use std::path::PathBuf;
+
+    fn test_file(name: &str) -> PathBuf {
+        ::std::env::temp_dir().join(format!("rstar-test-{}-{}.bin", name, ::std::process::id()))
+    }
+
+    #[test]
+    fn test_external_bulk_load_matches_hilbert_packing() {
+        for size in (0..20).map(|i| i * 97) {
+            let points = create_random_points(size, SEED_1);
+            // Use a small budget to create many runs and several merge passes
+            let tree = ExternalBulkLoader::new(64)
+                .load(points.iter().cloned())
+                .unwrap();
+            tree.check_invariants();
+            assert_eq!(tree.size(), points.len());
+            let expected = RTree::bulk_load_with_algorithm(points, BulkLoadAlgorithm::Hilbert);
+            assert!(tree.iter().eq(expected.iter()));
+        }
+    }
+
+    #[test]
+    fn test_external_bulk_load_with_config() {
+        let config = RTreeConfig {
+            max_size: 12,
+            min_size: 5,
+            ..Default::default()
+        };
+        let points = create_random_integers::<[i32; 3]>(1000, SEED_1);
+        let tree = ExternalBulkLoader::new(1024)
+            .config(config)
+            .temp_dir(::std::env::temp_dir())
+            .load(points.clone())
+            .unwrap();
+        tree.check_invariants();
+        assert_eq!(tree.config(), &config);
+        for point in &points {
+            assert!(tree.contains(point));
+        }
+    }
+
+    #[test]
+    fn test_external_bulk_load_with_invalid_directory() {
+        let loader = ExternalBulkLoader::new(1024).temp_dir("/this/directory/does/not/exist");
+        assert!(loader.load(vec![[0.0, 1.0]]).is_err());
+    }
+
+    #[test]
+    fn test_external_rtree_matches_in_memory_tree() {
+        let path = test_file("external-rtree");
+        let config = RTreeConfig {
+            max_size: 8,
+            min_size: 3,
+            ..Default::default()
+        };
+        let loader = ExternalBulkLoader::new(256).config(config);
+        let queries = create_random_points(20, SEED_2);
+        for &size in &[0, 1, 8, 9, 64, 65, 1000] {
+            let points = create_random_points(size, SEED_1);
+            let external = loader
+                .sort(points.clone())
+                .unwrap()
+                .write_to_file(&path)
+                .unwrap();
+            assert_eq!(external.size(), size);
+            let tree = loader.load(points.clone()).unwrap();
+            let reopened: ExternalRTree<[f64; 2]> = ExternalRTree::open(&path).unwrap();
+            assert_eq!(reopened.size(), size);
+            let everything = AABB::from_corners([-1.0, -1.0], [2.0, 2.0]);
+            let mut found = reopened.locate_in_envelope(&everything).unwrap();
+            found.sort_by(|l, r| l.partial_cmp(r).unwrap());
+            let mut expected = points.clone();
+            expected.sort_by(|l, r| l.partial_cmp(r).unwrap());
+            assert_eq!(found, expected);
+            for pair in queries.chunks(2) {
+                let query = AABB::from_corners(pair[0], pair[1]);
+                let mut found = reopened.locate_in_envelope_intersecting(&query).unwrap();
+                let mut expected: Vec<_> = tree.locate_in_envelope(&query).cloned().collect();
+                found.sort_by(|l, r| l.partial_cmp(r).unwrap());
+                expected.sort_by(|l, r| l.partial_cmp(r).unwrap());
+                assert_eq!(found, expected);
+            }
+        }
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_sort_within_bounds() {
+        let points = create_random_integers::<[i32; 3]>(1000, SEED_1);
+        // Some of the points lie outside of the given bounds
+        let bounds = AABB::from_corners([-100, -100, -100], [100, 100, 100]);
+        let sorted = ExternalBulkLoader::new(1024)
+            .sort_within(points.clone(), &bounds)
+            .unwrap();
+        assert_eq!(sorted.size(), points.len());
+        let tree = sorted.into_rtree().unwrap();
+        tree.check_invariants();
+        assert_eq!(tree.size(), points.len());
+        for point in &points {
+            assert!(tree.contains(point));
+        }
+    }
+
+    #[test]
+    fn test_open_invalid_file() {
+        let path = test_file("invalid");
+        fs::write(&path, b"RSTARPK1 but not a tree").unwrap();
+        assert!(ExternalRTree::<[f64; 2]>::open(&path).is_err());
+        fs::remove_file(&path).unwrap();
+        assert!(ExternalRTree::<[f64; 2]>::open(&path).is_err());
+    }
+
+    #[test]
+    fn test_array_serialization() {
+        let mut buffer = Vec::new();
+        [1.5f64, -2.0, 3.25].write_to(&mut buffer).unwrap();
+        [7i64, -8].write_to(&mut buffer).unwrap();
+        let mut reader = Cursor::new(buffer);
+        assert_eq!(
+            <[f64; 3]>::read_from(&mut reader).unwrap(),
+            [1.5, -2.0, 3.25]
+        );
+        assert_eq!(<[i64; 2]>::read_from(&mut reader).unwrap(), [7, -8]);
+        assert!(<[i64; 2]>::read_from(&mut reader).is_err());
+    }
+}
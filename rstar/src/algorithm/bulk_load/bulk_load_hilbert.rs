@@ -0,0 +1,119 @@
+use crate::algorithm::hilbert::hilbert_keys;
+use crate::envelope::Envelope;
+use crate::node::{ParentNode, RTreeNode};
+use crate::object::RTreeObject;
+use crate::params::RTreeConfig;
+
+use super::cluster_group_iterator::div_up;
+
+/// Packs consecutive runs of `max_size` nodes into new parent nodes.
+pub(super) fn pack<T>(nodes: Vec<RTreeNode<T>>, max_size: usize) -> Vec<RTreeNode<T>>
+where
+    T: RTreeObject,
+{
+    let mut result = Vec::with_capacity(div_up(nodes.len(), max_size));
+    let mut nodes = nodes.into_iter().peekable();
+    while nodes.peek().is_some() {
+        let children = nodes.by_ref().take(max_size).collect();
+        result.push(RTreeNode::Parent(ParentNode::new_parent(children)));
+    }
+    result
+}
+
+/// Packs elements into a tree in the order of their Hilbert values.
+///
+/// The elements are sorted once by the Hilbert value of their envelope's center. Consecutive
+/// runs of elements and nodes are then packed bottom up into completely filled nodes, except
+/// for the last node of each level.
+///
+/// See [Kamel, Ibrahim, and Christos Faloutsos. "On packing R-trees." Proceedings of the second international conference on Information and knowledge management. 1993.](https://doi.org/10.1145/170088.170403)
+pub fn bulk_load_hilbert<T>(elements: Vec<T>, config: &RTreeConfig) -> ParentNode<T>
+where
+    T: RTreeObject,
+{
+    let max_size = config.max_size;
+    let centers: Vec<_> = elements
+        .iter()
+        .map(|element| element.envelope().center())
+        .collect();
+    let mut elements: Vec<_> = hilbert_keys(&centers).into_iter().zip(elements).collect();
+    elements.sort_by_key(|(key, _)| *key);
+    let mut nodes: Vec<_> = elements
+        .into_iter()
+        .map(|(_, element)| RTreeNode::Leaf(element))
+        .collect();
+    while nodes.len() > max_size {
+        nodes = pack(nodes, max_size);
+    }
+    ParentNode::new_parent(nodes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::bulk_load_hilbert;
+    use crate::algorithm::bulk_load::BulkLoadAlgorithm;
+    use crate::node::{ParentNode, RTreeNode};
+    use crate::params::RTreeConfig;
+    use crate::test_utilities::*;
+    use crate::{RTree, RTreeObject};
+
+    #[test]
+    fn test_bulk_load_hilbert() {
+        for size in (0..50).map(|i| i * 37) {
+            let points = create_random_integers::<[i32; 2]>(size, SEED_1);
+            let tree = RTree::bulk_load_with_algorithm(points.clone(), BulkLoadAlgorithm::Hilbert);
+            tree.check_invariants();
+            assert_eq!(tree.size(), points.len());
+            for point in &points {
+                assert!(tree.contains(point));
+            }
+            let points = create_random_integers::<[i32; 4]>(size, SEED_2);
+            let tree = RTree::bulk_load_with_algorithm(points.clone(), BulkLoadAlgorithm::Hilbert);
+            tree.check_invariants();
+            assert_eq!(tree.size(), points.len());
+        }
+        let rectangles = create_random_rectangles(1000, SEED_1);
+        let tree = RTree::bulk_load_with_algorithm(rectangles.clone(), BulkLoadAlgorithm::Hilbert);
+        tree.check_invariants();
+        for rectangle in &rectangles {
+            assert!(tree.contains(rectangle));
+        }
+    }
+
+    #[test]
+    fn test_bulk_load_hilbert_packs_nodes() {
+        let config = RTreeConfig::default();
+        let root = bulk_load_hilbert(create_random_points(1000, SEED_1), &config);
+        let mut partially_filled = Vec::new();
+        collect_partially_filled_nodes(&root, 0, &mut partially_filled, config.max_size);
+        // At most the last node of each level is not completely filled
+        let mut levels = partially_filled.clone();
+        levels.sort_unstable();
+        levels.dedup();
+        assert_eq!(levels.len(), partially_filled.len());
+    }
+
+    #[test]
+    fn test_bulk_load_hilbert_is_deterministic() {
+        let points = create_random_points(500, SEED_1);
+        let first = RTree::bulk_load_with_algorithm(points.clone(), BulkLoadAlgorithm::Hilbert);
+        let second = RTree::bulk_load_with_algorithm(points, BulkLoadAlgorithm::Hilbert);
+        assert!(first.iter().eq(second.iter()));
+    }
+
+    fn collect_partially_filled_nodes<T: RTreeObject>(
+        node: &ParentNode<T>,
+        depth: usize,
+        result: &mut Vec<usize>,
+        max_size: usize,
+    ) {
+        if depth > 0 && node.children.len() < max_size {
+            result.push(depth);
+        }
+        for child in &node.children {
+            if let RTreeNode::Parent(ref data) = child {
+                collect_partially_filled_nodes(data, depth + 1, result, max_size);
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::envelope::Envelope;
+use crate::node::{ParentNode, RTreeNode};
+use crate::object::RTreeObject;
+use crate::params::RTreeConfig;
+use crate::point::Point;
+use rayon::prelude::*;
+
+use super::cluster_group_iterator::{calculate_number_of_clusters_on_axis, ClusterGroupIterator};
+
+fn bulk_load_recursive<T>(elements: Vec<T>, config: &RTreeConfig) -> ParentNode<T>
+where
+    T: RTreeObject + Send,
+    T::Envelope: Send,
+{
+    let m = config.max_size;
+    if elements.len() <= m {
+        // Reached leaf level
+        let elements: Vec<_> = elements.into_iter().map(RTreeNode::Leaf).collect();
+        return ParentNode::new_parent(elements);
+    }
+    let number_of_clusters_on_axis = calculate_number_of_clusters_on_axis::<T>(elements.len(), m);
+    let clusters = partition(
+        elements,
+        <T::Envelope as Envelope>::Point::DIMENSIONS,
+        number_of_clusters_on_axis,
+    );
+    let children = clusters
+        .into_par_iter()
+        .map(|cluster| RTreeNode::Parent(bulk_load_recursive(cluster, config)))
+        .collect();
+    ParentNode::new_parent(children)
+}
+
+/// Partitions the given elements into clusters along all axes up to `current_axis`.
+///
+/// The resulting clusters are returned in the order in which the sequential `PartitioningTask`
+/// yields them to ensure that both bulk loaders create identical trees.
+fn partition<T>(
+    elements: Vec<T>,
+    current_axis: usize,
+    number_of_clusters_on_axis: usize,
+) -> Vec<Vec<T>>
+where
+    T: RTreeObject + Send,
+{
+    if current_axis == 0 {
+        return vec![elements];
+    }
+    let slabs: Vec<_> =
+        ClusterGroupIterator::new(elements, number_of_clusters_on_axis, current_axis - 1).collect();
+    let clusters: Vec<_> = slabs
+        .into_par_iter()
+        .rev()
+        .map(|slab| partition(slab, current_axis - 1, number_of_clusters_on_axis))
+        .collect();
+    clusters.into_iter().flatten().collect()
+}
+
+/// A parallel implementation of the OMT bulk loading algorithm.
+///
+/// Yields the same tree as [super::bulk_load_sequential] but partitions elements and creates
+/// independent subtrees concurrently on rayon's global thread pool.
+pub fn bulk_load_parallel<T>(elements: Vec<T>, config: &RTreeConfig) -> ParentNode<T>
+where
+    T: RTreeObject + Send,
+    T::Envelope: Send,
+{
+    bulk_load_recursive(elements, config)
+}
+
+#[cfg(test)]
+mod test {
+    use super::bulk_load_parallel;
+    use crate::algorithm::bulk_load::bulk_load_sequential;
+    use crate::node::{ParentNode, RTreeNode};
+    use crate::params::RTreeConfig;
+    use crate::test_utilities::*;
+    use crate::{RTree, RTreeObject};
+    use std::fmt::Debug;
+
+    #[test]
+    fn test_bulk_load_parallel_matches_sequential() {
+        let config = RTreeConfig::default();
+        for size in (0..60).map(|i| i * 31) {
+            let points = create_random_integers::<[i32; 2]>(size, SEED_1);
+            check_trees_equal(
+                &bulk_load_parallel(points.clone(), &config),
+                &bulk_load_sequential(points, &config),
+            );
+            let points = create_random_integers::<[i32; 3]>(size, SEED_2);
+            check_trees_equal(
+                &bulk_load_parallel(points.clone(), &config),
+                &bulk_load_sequential(points, &config),
+            );
+        }
+    }
+
+    #[test]
+    fn test_bulk_load_parallel() {
+        let points = create_random_points(3000, SEED_1);
+        let tree = RTree::bulk_load_parallel(points.clone());
+        tree.check_invariants();
+        assert_eq!(tree.size(), points.len());
+        for point in &points {
+            assert!(tree.contains(point));
+        }
+        let empty: RTree<[f64; 2]> = RTree::bulk_load_parallel(Vec::new());
+        assert_eq!(empty.size(), 0);
+    }
+
+    fn check_trees_equal<T>(first: &ParentNode<T>, second: &ParentNode<T>)
+    where
+        T: RTreeObject + PartialEq + Debug,
+    {
+        assert_eq!(first.envelope, second.envelope);
+        assert_eq!(first.children.len(), second.children.len());
+        for (first, second) in first.children.iter().zip(&second.children) {
+            match (first, second) {
+                (RTreeNode::Leaf(first), RTreeNode::Leaf(second)) => assert_eq!(first, second),
+                (RTreeNode::Parent(first), RTreeNode::Parent(second)) => {
+                    check_trees_equal(first, second)
+                }
+                _ => panic!("Trees differ in their structure"),
+            }
+        }
+    }
+}
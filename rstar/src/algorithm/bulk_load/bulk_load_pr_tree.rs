@@ -0,0 +1,169 @@
+use crate::aabb::AABB;
+use crate::node::{ParentNode, RTreeNode};
+use crate::object::RTreeObject;
+use crate::params::RTreeConfig;
+use crate::point::Point;
+use std::cmp::Ordering;
+
+/// Returns the coordinate of an envelope when it is considered as a point with `2 * D`
+/// dimensions.
+///
+/// The first `D` coordinates are the envelope's lower corner, the remaining coordinates its
+/// upper corner.
+fn coordinate<P: Point>(envelope: &AABB<P>, index: usize) -> P::Scalar {
+    if index < P::DIMENSIONS {
+        envelope.lower().nth(index)
+    } else {
+        envelope.upper().nth(index - P::DIMENSIONS)
+    }
+}
+
+/// Compares two nodes by how extreme they are in a given direction.
+///
+/// Nodes with the smallest lower corner coordinate are most extreme for the first `D`
+/// directions, nodes with the largest upper corner coordinate for the remaining directions.
+fn compare_extremes<T, P>(direction: usize, l: &RTreeNode<T>, r: &RTreeNode<T>) -> Ordering
+where
+    T: RTreeObject<Envelope = AABB<P>>,
+    P: Point,
+{
+    let l = coordinate(&l.envelope(), direction);
+    let r = coordinate(&r.envelope(), direction);
+    if direction < P::DIMENSIONS {
+        l.partial_cmp(&r).unwrap()
+    } else {
+        r.partial_cmp(&l).unwrap()
+    }
+}
+
+/// Groups the given nodes into the leaves of a pseudo PR-tree.
+///
+/// Each pseudo PR-tree node first removes the `max_size` most extreme nodes in each of the
+/// `2 * D` directions into priority leaves. The remaining nodes are split at their median along
+/// `split_axis` of the `2 * D` dimensional space and distributed to two subtrees.
+fn pseudo_pr_tree_leaves<T, P>(
+    mut nodes: Vec<RTreeNode<T>>,
+    split_axis: usize,
+    max_size: usize,
+    result: &mut Vec<Vec<RTreeNode<T>>>,
+) where
+    T: RTreeObject<Envelope = AABB<P>>,
+    P: Point,
+{
+    let dimensions = 2 * P::DIMENSIONS;
+    for direction in 0..dimensions {
+        if nodes.len() <= max_size {
+            if !nodes.is_empty() {
+                result.push(nodes);
+            }
+            return;
+        }
+        ::pdqselect::select_by(&mut nodes, max_size, |l, r| {
+            compare_extremes(direction, l, r)
+        });
+        let remaining = nodes.split_off(max_size);
+        result.push(::std::mem::replace(&mut nodes, remaining));
+    }
+    if nodes.len() <= max_size {
+        if !nodes.is_empty() {
+            result.push(nodes);
+        }
+        return;
+    }
+    let median = nodes.len() / 2;
+    ::pdqselect::select_by(&mut nodes, median, |l, r| {
+        coordinate(&l.envelope(), split_axis)
+            .partial_cmp(&coordinate(&r.envelope(), split_axis))
+            .unwrap()
+    });
+    let second_half = nodes.split_off(median);
+    let next_axis = (split_axis + 1) % dimensions;
+    pseudo_pr_tree_leaves(nodes, next_axis, max_size, result);
+    pseudo_pr_tree_leaves(second_half, next_axis, max_size, result);
+}
+
+/// An implementation of the Priority R-tree (PR-tree) bulk loading algorithm.
+///
+/// The tree is built bottom up. Each level consists of the leaves of a pseudo PR-tree that is
+/// built on the envelopes of the level below.
+///
+/// See https://doi.org/10.1145/1328911.1328920
+pub fn bulk_load_pr_tree<T, P>(elements: Vec<T>, config: &RTreeConfig) -> ParentNode<T>
+where
+    T: RTreeObject<Envelope = AABB<P>>,
+    P: Point,
+{
+    let max_size = config.max_size;
+    let mut nodes: Vec<_> = elements.into_iter().map(RTreeNode::Leaf).collect();
+    while nodes.len() > max_size {
+        let mut groups = Vec::new();
+        pseudo_pr_tree_leaves(nodes, 0, max_size, &mut groups);
+        nodes = groups
+            .into_iter()
+            .map(|children| RTreeNode::Parent(ParentNode::new_parent(children)))
+            .collect();
+    }
+    ParentNode::new_parent(nodes)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::primitives::Rectangle;
+    use crate::test_utilities::*;
+    use crate::{Envelope, RTree, RTreeObject};
+
+    #[test]
+    fn test_bulk_load_pr_tree() {
+        for size in (0..50).map(|i| i * 37) {
+            let points = create_random_integers::<[i32; 2]>(size, SEED_1);
+            let tree = RTree::bulk_load_pr_tree(points.clone());
+            tree.check_invariants();
+            assert_eq!(tree.size(), points.len());
+            for point in &points {
+                assert!(tree.contains(point));
+            }
+            let points = create_random_integers::<[i32; 3]>(size, SEED_2);
+            let tree = RTree::bulk_load_pr_tree(points.clone());
+            tree.check_invariants();
+            assert_eq!(tree.size(), points.len());
+        }
+        let rectangles = create_random_rectangles(1000, SEED_1);
+        let tree = RTree::bulk_load_pr_tree(rectangles.clone());
+        tree.check_invariants();
+        for rectangle in &rectangles {
+            assert!(tree.contains(rectangle));
+        }
+    }
+
+    #[test]
+    fn test_bulk_load_pr_tree_with_skewed_rectangles() {
+        // Long, thin rectangles of very different sizes
+        let points = create_random_points(1000, SEED_1);
+        let rectangles: Vec<_> = points
+            .iter()
+            .enumerate()
+            .map(|(index, [x, y])| {
+                let length = (index % 100) as f64;
+                if index % 2 == 0 {
+                    Rectangle::from_corners([*x, *y], [x + length, y + 0.0001])
+                } else {
+                    Rectangle::from_corners([*x, *y], [x + 0.0001, y + length])
+                }
+            })
+            .collect();
+        let tree = RTree::bulk_load_pr_tree(rectangles.clone());
+        tree.check_invariants();
+        for query in create_random_rectangles(100, SEED_2) {
+            let query = query.envelope();
+            let expected = rectangles
+                .iter()
+                .filter(|rectangle| query.intersects(&rectangle.envelope()))
+                .count();
+            let found: Vec<_> = tree.locate_in_envelope_intersecting(&query).collect();
+            assert_eq!(found.len(), expected);
+            assert!(found
+                .iter()
+                .all(|rectangle| query.intersects(&rectangle.envelope())));
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use crate::aabb::AABB;
+use crate::envelope::Envelope;
+use crate::node::{ParentNode, RTreeNode};
+use crate::object::RTreeObject;
+use crate::params::RTreeConfig;
+use crate::point::{scalar_from_usize, Point, PointExt};
+use num_traits::{One, Zero};
+
+use super::cluster_group_iterator::div_up;
+
+/// The number of bins per axis that are evaluated for each split.
+const NUMBER_OF_BINS: usize = 16;
+
+/// Returns the surface area of an envelope, up to a constant factor.
+///
+/// The surface area of a box is the sum of the areas of its faces. In two dimensions, this is
+/// the box's perimeter.
+pub(crate) fn surface_area<P: Point>(envelope: &AABB<P>) -> P::Scalar {
+    let lower = envelope.lower();
+    let upper = envelope.upper();
+    let mut result = Zero::zero();
+    for excluded_axis in 0..P::DIMENSIONS {
+        let mut face = P::Scalar::one();
+        for axis in (0..P::DIMENSIONS).filter(|axis| *axis != excluded_axis) {
+            face = face * (upper.nth(axis) - lower.nth(axis));
+        }
+        result = result + face;
+    }
+    result
+}
+
+/// A candidate split: elements whose center lies below `boundary` along `axis` form the first
+/// group.
+struct SplitCandidate<S> {
+    cost: S,
+    axis: usize,
+    boundary: S,
+}
+
+/// Finds the split with the lowest surface area heuristic cost.
+///
+/// Only splits that do not increase the number of required subtrees of size `capacity` are
+/// considered.
+fn find_best_split<T, P>(elements: &[T], capacity: usize) -> Option<SplitCandidate<P::Scalar>>
+where
+    T: RTreeObject<Envelope = AABB<P>>,
+    P: Point,
+{
+    let groups = div_up(elements.len(), capacity);
+    let centers: Vec<_> = elements.iter().map(|e| e.envelope().center()).collect();
+    let first = centers[0];
+    let (lower, upper) = centers
+        .iter()
+        .fold((first, first), |(lower, upper), center| {
+            (lower.min_point(center), upper.max_point(center))
+        });
+    let number_of_bins: P::Scalar = scalar_from_usize(NUMBER_OF_BINS);
+    let mut best: Option<SplitCandidate<P::Scalar>> = None;
+    for axis in 0..P::DIMENSIONS {
+        let (min, max) = (lower.nth(axis), upper.nth(axis));
+        if min == max {
+            continue;
+        }
+        let boundaries: Vec<_> = (1..NUMBER_OF_BINS)
+            .map(|bin| min + (max - min) * scalar_from_usize(bin) / number_of_bins)
+            .collect();
+        let mut bin_envelopes = [AABB::new_empty(); NUMBER_OF_BINS];
+        let mut bin_counts = [0; NUMBER_OF_BINS];
+        for (element, center) in elements.iter().zip(&centers) {
+            let value = center.nth(axis);
+            // The boundaries are sorted, the bin is given by the number of boundaries below
+            let bin = boundaries
+                .iter()
+                .take_while(|boundary| **boundary <= value)
+                .count();
+            bin_envelopes[bin].merge(&element.envelope());
+            bin_counts[bin] += 1;
+        }
+        let mut suffixes = [(AABB::new_empty(), 0); NUMBER_OF_BINS + 1];
+        for bin in (0..NUMBER_OF_BINS).rev() {
+            let (envelope, count) = suffixes[bin + 1];
+            suffixes[bin] = (
+                envelope.merged(&bin_envelopes[bin]),
+                count + bin_counts[bin],
+            );
+        }
+        let mut prefix_envelope = AABB::new_empty();
+        let mut prefix_count = 0;
+        for bin in 0..NUMBER_OF_BINS - 1 {
+            prefix_envelope.merge(&bin_envelopes[bin]);
+            prefix_count += bin_counts[bin];
+            let (suffix_envelope, suffix_count) = suffixes[bin + 1];
+            if prefix_count == 0
+                || suffix_count == 0
+                || div_up(prefix_count, capacity) + div_up(suffix_count, capacity) > groups
+            {
+                continue;
+            }
+            let cost = surface_area(&prefix_envelope) * scalar_from_usize(prefix_count)
+                + surface_area(&suffix_envelope) * scalar_from_usize(suffix_count);
+            if best.as_ref().map(|best| cost < best.cost).unwrap_or(true) {
+                best = Some(SplitCandidate {
+                    cost,
+                    axis,
+                    boundary: boundaries[bin],
+                });
+            }
+        }
+    }
+    best
+}
+
+/// Splits the elements into groups of at most `capacity` elements.
+fn split_into_groups<T, P>(mut elements: Vec<T>, capacity: usize, result: &mut Vec<Vec<T>>)
+where
+    T: RTreeObject<Envelope = AABB<P>>,
+    P: Point,
+{
+    if elements.len() <= capacity {
+        result.push(elements);
+        return;
+    }
+    let (first, second) = match find_best_split(&elements, capacity) {
+        Some(SplitCandidate { axis, boundary, .. }) => elements
+            .into_iter()
+            .partition(|element| element.envelope().center().nth(axis) < boundary),
+        None => {
+            // No bin boundary yields a valid split, split by count instead
+            let groups = div_up(elements.len(), capacity);
+            let split_index = capacity * (groups / 2);
+            ::pdqselect::select_by(&mut elements, split_index, |l, r| {
+                l.envelope()
+                    .center()
+                    .nth(0)
+                    .partial_cmp(&r.envelope().center().nth(0))
+                    .unwrap()
+            });
+            let second = elements.split_off(split_index);
+            (elements, second)
+        }
+    };
+    split_into_groups(first, capacity, result);
+    split_into_groups(second, capacity, result);
+}
+
+fn bulk_load_recursive<T, P>(elements: Vec<T>, height: usize, max_size: usize) -> ParentNode<T>
+where
+    T: RTreeObject<Envelope = AABB<P>>,
+    P: Point,
+{
+    if height <= 1 {
+        // Reached leaf level
+        let elements: Vec<_> = elements.into_iter().map(RTreeNode::Leaf).collect();
+        return ParentNode::new_parent(elements);
+    }
+    let capacity = max_size.pow(height as u32 - 1);
+    let mut groups = Vec::with_capacity(max_size);
+    split_into_groups(elements, capacity, &mut groups);
+    let children = groups
+        .into_iter()
+        .map(|group| RTreeNode::Parent(bulk_load_recursive(group, height - 1, max_size)))
+        .collect();
+    ParentNode::new_parent(children)
+}
+
+/// A top-down bulk loading algorithm using the binned surface area heuristic (SAH).
+///
+/// Each node's elements are recursively split in two. Every split is chosen among
+/// `NUMBER_OF_BINS` candidate positions per axis to minimize the sum of the surface areas of
+/// both halves, weighted by their number of elements. Splits never increase the number of
+/// children that are required to hold all elements, keeping the tree's height minimal.
+///
+/// See https://doi.org/10.1109/RT.2007.4342588
+pub fn bulk_load_sah<T, P>(elements: Vec<T>, config: &RTreeConfig) -> ParentNode<T>
+where
+    T: RTreeObject<Envelope = AABB<P>>,
+    P: Point,
+{
+    let max_size = config.max_size;
+    let mut height = 1;
+    let mut capacity = max_size;
+    while capacity < elements.len() {
+        capacity *= max_size;
+        height += 1;
+    }
+    bulk_load_recursive(elements, height, max_size)
+}
+
+#[cfg(test)]
+mod test {
+    use super::surface_area;
+    use crate::node::{ParentNode, RTreeNode};
+    use crate::test_utilities::*;
+    use crate::{RTree, RTreeObject, AABB};
+    use rand::{Rng, SeedableRng};
+    use rand_hc::Hc128Rng;
+
+    #[test]
+    fn test_surface_area() {
+        assert_eq!(
+            surface_area(&AABB::from_corners([0.0, 0.0], [2.0, 3.0])),
+            5.0
+        );
+        assert_eq!(
+            surface_area(&AABB::from_corners([0.0, 0.0, 0.0], [2.0, 3.0, 4.0])),
+            26.0
+        );
+    }
+
+    #[test]
+    fn test_bulk_load_sah() {
+        let mut rng = Hc128Rng::from_seed(*SEED_2);
+        for size in (0..50).map(|i| i * 37) {
+            let points = create_random_points(size, SEED_1);
+            let tree = RTree::bulk_load_sah(points.clone());
+            tree.check_invariants();
+            assert_eq!(tree.size(), points.len());
+            for point in &points {
+                assert!(tree.contains(point));
+            }
+            let points: Vec<[f64; 3]> = (0..size).map(|_| rng.gen()).collect();
+            let tree = RTree::bulk_load_sah(points.clone());
+            tree.check_invariants();
+            assert_eq!(tree.size(), points.len());
+        }
+        let grid: Vec<_> = (0..1000).map(|i| [i % 37, i / 37]).collect();
+        let tree = RTree::bulk_load_sah(grid.clone());
+        tree.check_invariants();
+        for point in &grid {
+            assert!(tree.contains(point));
+        }
+        let tree = RTree::bulk_load_sah(vec![[1.0, 1.0]; 100]);
+        tree.check_invariants();
+        assert_eq!(tree.size(), 100);
+    }
+
+    #[test]
+    fn test_bulk_load_sah_reduces_surface_area() {
+        let rectangles = create_random_rectangles(2000, SEED_1);
+        let sah_tree = RTree::bulk_load_sah(rectangles.clone());
+        let omt_tree = RTree::bulk_load(rectangles);
+        sah_tree.check_invariants();
+        assert!(total_surface_area(sah_tree.root()) < total_surface_area(omt_tree.root()));
+    }
+
+    fn total_surface_area<T>(node: &ParentNode<T>) -> f64
+    where
+        T: RTreeObject<Envelope = AABB<[f64; 2]>>,
+    {
+        surface_area(&node.envelope)
+            + node
+                .children
+                .iter()
+                .map(|child| match child {
+                    RTreeNode::Parent(ref data) => total_surface_area(data),
+                    RTreeNode::Leaf(_) => 0.0,
+                })
+                .sum::<f64>()
+    }
+}
@@ -0,0 +1,135 @@
+use crate::envelope::Envelope;
+use crate::node::{ParentNode, RTreeNode};
+use crate::object::RTreeObject;
+use crate::params::RTreeConfig;
+use crate::point::Point;
+
+use super::cluster_group_iterator::div_up;
+
+/// Packs the given nodes into parent nodes of `max_size` children, starting at `axis`.
+///
+/// The nodes are sorted along `axis` and cut into slices. Each slice is tiled recursively
+/// along the remaining axes. Along the last axis, consecutive runs of `max_size` nodes form
+/// a new parent node.
+fn tile<T>(
+    mut nodes: Vec<RTreeNode<T>>,
+    axis: usize,
+    max_size: usize,
+    result: &mut Vec<RTreeNode<T>>,
+) where
+    T: RTreeObject,
+{
+    let remaining_dimensions = <T::Envelope as Envelope>::Point::DIMENSIONS - axis;
+    let number_of_pages = div_up(nodes.len(), max_size);
+    T::Envelope::sort_envelopes(axis, &mut nodes);
+    let slice_size = if remaining_dimensions == 1 {
+        max_size
+    } else {
+        let number_of_slices = (number_of_pages as f64)
+            .powf(1. / remaining_dimensions as f64)
+            .ceil() as usize;
+        max_size * div_up(number_of_pages, number_of_slices)
+    };
+    // Split off the slices from the back to avoid moving the remaining nodes repeatedly
+    let mut slices = Vec::with_capacity(div_up(nodes.len(), slice_size));
+    while !nodes.is_empty() {
+        let start = (nodes.len() - 1) / slice_size * slice_size;
+        slices.push(nodes.split_off(start));
+    }
+    for slice in slices.into_iter().rev() {
+        if remaining_dimensions == 1 {
+            result.push(RTreeNode::Parent(ParentNode::new_parent(slice)));
+        } else {
+            tile(slice, axis + 1, max_size, result);
+        }
+    }
+}
+
+/// An implementation of the Sort-Tile-Recursive (STR) packing algorithm.
+///
+/// The tree is built bottom up. Each level is packed into completely filled nodes, except for
+/// the last node of each slice.
+///
+/// See https://doi.org/10.1109/ICDE.1997.582015
+pub fn bulk_load_str<T>(elements: Vec<T>, config: &RTreeConfig) -> ParentNode<T>
+where
+    T: RTreeObject,
+{
+    let max_size = config.max_size;
+    let mut nodes: Vec<_> = elements.into_iter().map(RTreeNode::Leaf).collect();
+    while nodes.len() > max_size {
+        let mut parents = Vec::with_capacity(div_up(nodes.len(), max_size));
+        tile(nodes, 0, max_size, &mut parents);
+        nodes = parents;
+    }
+    ParentNode::new_parent(nodes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{bulk_load_str, div_up};
+    use crate::algorithm::bulk_load::BulkLoadAlgorithm;
+    use crate::node::{ParentNode, RTreeNode};
+    use crate::params::RTreeConfig;
+    use crate::test_utilities::*;
+    use crate::{RTree, RTreeObject};
+
+    #[test]
+    fn test_bulk_load_str() {
+        for size in (0..50).map(|i| i * 37) {
+            let points = create_random_integers::<[i32; 2]>(size, SEED_1);
+            let tree = RTree::bulk_load_with_algorithm(points.clone(), BulkLoadAlgorithm::Str);
+            tree.check_invariants();
+            assert_eq!(tree.size(), points.len());
+            for point in &points {
+                assert!(tree.contains(point));
+            }
+            let points = create_random_integers::<[i32; 3]>(size, SEED_2);
+            let tree = RTree::bulk_load_with_algorithm(points.clone(), BulkLoadAlgorithm::Str);
+            tree.check_invariants();
+            assert_eq!(tree.size(), points.len());
+        }
+    }
+
+    #[test]
+    fn test_bulk_load_str_packs_nodes() {
+        let config = RTreeConfig::default();
+        // 36 * 36 points can be divided into completely filled nodes on every level
+        let points: Vec<_> = (0..36 * 36).map(|i| [i % 36, i / 36]).collect();
+        let root = bulk_load_str(points, &config);
+        assert_eq!(count_nodes(&root), 36 * 6 + 36 + 6 + 1);
+
+        // Partially filled nodes are rare, this sample still yields the minimal number of leaves
+        let points = create_random_points(1000, SEED_1);
+        let root = bulk_load_str(points, &config);
+        let leaf_parents = count_nodes(&root) - count_nodes_above_leaves(&root);
+        assert_eq!(leaf_parents, div_up(1000, config.max_size));
+    }
+
+    fn count_nodes<T: RTreeObject>(node: &ParentNode<T>) -> usize {
+        1 + node
+            .children
+            .iter()
+            .map(|child| match child {
+                RTreeNode::Parent(ref data) => count_nodes(data),
+                RTreeNode::Leaf(_) => 0,
+            })
+            .sum::<usize>()
+    }
+
+    fn count_nodes_above_leaves<T: RTreeObject>(node: &ParentNode<T>) -> usize {
+        match node.children.first() {
+            Some(RTreeNode::Parent(_)) => {
+                1 + node
+                    .children
+                    .iter()
+                    .map(|child| match child {
+                        RTreeNode::Parent(ref data) => count_nodes_above_leaves(data),
+                        RTreeNode::Leaf(_) => 0,
+                    })
+                    .sum::<usize>()
+            }
+            _ => 0,
+        }
+    }
+}
@@ -1,4 +1,75 @@
+mod bulk_load_external;
+mod bulk_load_hilbert;
+#[cfg(feature = "rayon")]
+mod bulk_load_parallel;
+mod bulk_load_pr_tree;
+mod bulk_load_sah;
 mod bulk_load_sequential;
+mod bulk_load_str;
 mod cluster_group_iterator;
 
+pub use self::bulk_load_external::{
+    ExternalBulkLoader, ExternalElement, ExternalRTree, SortedElements,
+};
+pub use self::bulk_load_hilbert::bulk_load_hilbert;
+#[cfg(feature = "rayon")]
+pub use self::bulk_load_parallel::bulk_load_parallel;
+pub use self::bulk_load_pr_tree::bulk_load_pr_tree;
+pub use self::bulk_load_sah::bulk_load_sah;
 pub use self::bulk_load_sequential::bulk_load_sequential;
+pub use self::bulk_load_str::bulk_load_str;
+pub(crate) use self::cluster_group_iterator::div_up;
+
+use crate::node::ParentNode;
+use crate::object::RTreeObject;
+use crate::params::RTreeConfig;
+
+/// Selects the algorithm used by [RTree::bulk_load_with_algorithm](crate::RTree::bulk_load_with_algorithm).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BulkLoadAlgorithm {
+    /// The overlap minimizing top-down algorithm (OMT) which is used by
+    /// [RTree::bulk_load](crate::RTree::bulk_load).
+    Omt,
+    /// The Sort-Tile-Recursive algorithm (STR).
+    ///
+    /// STR sorts the elements along the first axis into slices and tiles each slice along the
+    /// remaining axes. Its nodes are completely filled, which reduces the tree's memory usage
+    /// compared to OMT. Query performance is usually similar.
+    ///
+    /// See [Leutenegger, Scott T., Mario A. Lopez, and Jeffrey Edgington. "STR: A simple and efficient algorithm for R-tree packing." Proceedings 13th International Conference on Data Engineering. IEEE, 1997.](https://doi.org/10.1109/ICDE.1997.582015)
+    Str,
+    /// Packs the elements in the order of their Hilbert values.
+    ///
+    /// The elements are sorted once by the Hilbert value of their envelope's center, consecutive
+    /// runs of elements are then packed bottom up into completely filled nodes. This is usually
+    /// the fastest algorithm and yields a deterministic layout, but the resulting nodes tend to
+    /// overlap more than those of OMT or STR.
+    ///
+    /// See [Kamel, Ibrahim, and Christos Faloutsos. "On packing R-trees." Proceedings of the second international conference on Information and knowledge management. 1993.](https://doi.org/10.1145/170088.170403)
+    Hilbert,
+}
+
+// Deriving `Default` for enums requires a newer compiler than the supported minimum
+#[allow(clippy::derivable_impls)]
+impl Default for BulkLoadAlgorithm {
+    fn default() -> Self {
+        BulkLoadAlgorithm::Omt
+    }
+}
+
+// The priority r-tree and the surface area heuristic require access to the corners of an
+// envelope and are thus not part of `BulkLoadAlgorithm`, see `RTree::bulk_load_pr_tree` and
+// `RTree::bulk_load_sah`.
+
+impl BulkLoadAlgorithm {
+    pub(crate) fn bulk_load<T>(self) -> fn(Vec<T>, &RTreeConfig) -> ParentNode<T>
+    where
+        T: RTreeObject,
+    {
+        match self {
+            BulkLoadAlgorithm::Omt => bulk_load_sequential,
+            BulkLoadAlgorithm::Str => bulk_load_str,
+            BulkLoadAlgorithm::Hilbert => bulk_load_hilbert,
+        }
+    }
+}
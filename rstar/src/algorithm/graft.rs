@@ -0,0 +1,112 @@
+use crate::algorithm::rstar::insert_at_depth;
+use crate::node::{ParentNode, RTreeNode};
+use crate::object::RTreeObject;
+use crate::params::RTreeConfig;
+
+/// Returns the number of parent node levels of a (sub)tree, including `node` itself.
+///
+/// A node that only contains leaves (or no children at all) has a height of 1.
+pub fn height<T>(node: &ParentNode<T>) -> usize
+where
+    T: RTreeObject,
+{
+    let mut height = 1;
+    let mut current = node;
+    while let Some(RTreeNode::Parent(ref child)) = current.children.first() {
+        height += 1;
+        current = child;
+    }
+    height
+}
+
+/// Inserts all elements of a subtree into a tree, keeping the subtree's nodes intact whenever
+/// possible.
+///
+/// The subtree is attached at the level matching its height, subtrees with less than
+/// `MIN_SIZE` children or with the same height as the tree are dissolved and their children are
+/// attached one level further down. If the subtree is higher than the tree, both trees switch
+/// their roles.
+///
+/// Returns the new height of the tree.
+pub fn graft<T>(
+    root: &mut ParentNode<T>,
+    root_height: usize,
+    subtree: ParentNode<T>,
+    subtree_height: usize,
+    config: &RTreeConfig,
+) -> usize
+where
+    T: RTreeObject,
+{
+    if subtree_height > root_height {
+        let old_root = ::std::mem::replace(root, subtree);
+        return graft(root, subtree_height, old_root, root_height, config);
+    }
+    let mut root_height = root_height;
+    if subtree_height < root_height && subtree.children.len() >= config.min_size {
+        let depth = root_height - subtree_height - 1;
+        if insert_at_depth(root, RTreeNode::Parent(subtree), depth, config) {
+            root_height += 1;
+        }
+        return root_height;
+    }
+    for child in subtree.children {
+        match child {
+            RTreeNode::Leaf(_) => {
+                if insert_at_depth(root, child, root_height - 1, config) {
+                    root_height += 1;
+                }
+            }
+            RTreeNode::Parent(data) => {
+                root_height = graft(root, root_height, data, subtree_height - 1, config);
+            }
+        }
+    }
+    root_height
+}
+
+#[cfg(test)]
+mod test {
+    use super::{graft, height};
+    use crate::algorithm::bulk_load::bulk_load_sequential;
+    use crate::algorithm::iterators::RTreeIterator;
+    use crate::algorithm::selection_functions::SelectAllFunc;
+    use crate::node::ParentNode;
+    use crate::test_utilities::{create_random_points, SEED_1, SEED_2};
+    use crate::RTreeConfig;
+
+    #[test]
+    fn test_graft_subtrees_of_different_heights() {
+        let config = RTreeConfig::default();
+        for &(size1, size2) in &[(1000, 50), (50, 1000), (300, 300), (2, 1000), (1000, 2)] {
+            let points1 = create_random_points(size1, SEED_1);
+            let points2 = create_random_points(size2, SEED_2);
+            let mut root = bulk_load_sequential(points1.clone(), &config);
+            let subtree = bulk_load_sequential(points2.clone(), &config);
+            let (root_height, subtree_height) = (height(&root), height(&subtree));
+            let new_height = graft(&mut root, root_height, subtree, subtree_height, &config);
+            assert_eq!(new_height, height(&root));
+            assert_eq!(root.sanity_check_unsized(), Some(new_height));
+
+            let mut elements: Vec<_> = RTreeIterator::new(&root, SelectAllFunc).collect();
+            let mut expected: Vec<_> = points1.iter().chain(&points2).collect();
+            elements.sort_by(|l, r| l.partial_cmp(r).unwrap());
+            expected.sort_by(|l, r| l.partial_cmp(r).unwrap());
+            assert_eq!(elements, expected);
+        }
+    }
+
+    #[test]
+    fn test_graft_into_empty_root() {
+        let config = RTreeConfig::default();
+        let points = create_random_points(100, SEED_1);
+        let mut root = ParentNode::new_root(&config);
+        let subtree = bulk_load_sequential(points, &config);
+        let subtree_height = height(&subtree);
+        assert_eq!(
+            graft(&mut root, 1, subtree, subtree_height, &config),
+            subtree_height
+        );
+        assert_eq!(RTreeIterator::new(&root, SelectAllFunc).count(), 100);
+    }
+}
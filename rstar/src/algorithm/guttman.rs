@@ -0,0 +1,422 @@
+use crate::envelope::Envelope;
+use crate::node::{ParentNode, RTreeNode};
+use crate::object::RTreeObject;
+use crate::params::{InsertionStrategy, RTreeConfig, RTreeParams};
+use crate::point::Point;
+use crate::rtree::RTree;
+use num_traits::{Bounded, Signed, Zero};
+
+/// Inserts elements according to Guttman's original r-tree heuristic using a linear split.
+///
+/// Guttman's heuristics choose the subtree that needs the least area enlargement and never
+/// reinsert any nodes. This makes insertion considerably cheaper than
+/// [R* insertion](crate::RStarInsertionStrategy) at the cost of a worse tree structure and thus
+/// slower queries.
+///
+/// The linear split picks the two children with the greatest separation along any axis,
+/// normalized by the width of all children along that axis, as seeds and distributes the
+/// remaining children in a single pass. Its runtime is linear in the node size.
+///
+/// # Example
+/// ```
+/// use rstar::{RTree, RTreeParams, GuttmanLinearInsertionStrategy};
+///
+/// struct LinearParams;
+///
+/// impl RTreeParams for LinearParams {
+///     const MIN_SIZE: usize = 4;
+///     const MAX_SIZE: usize = 10;
+///     const REINSERTION_COUNT: usize = 0;
+///     type DefaultInsertionStrategy = GuttmanLinearInsertionStrategy;
+/// }
+///
+/// let mut tree: RTree<_, LinearParams> = RTree::new_with_params();
+/// tree.insert([0.0, 1.0]);
+/// assert_eq!(tree.size(), 1);
+/// ```
+pub enum GuttmanLinearInsertionStrategy {}
+
+/// Inserts elements according to Guttman's original r-tree heuristic using a quadratic split.
+///
+/// The quadratic split picks the pair of children that would waste the most area if put into
+/// the same node as seeds. The remaining children are added one by one, always choosing the
+/// child with the strongest preference for one of the groups next. Its runtime is quadratic in
+/// the node size but usually yields better trees than the
+/// [linear split](GuttmanLinearInsertionStrategy).
+///
+/// See [GuttmanLinearInsertionStrategy] for more information on Guttman's heuristics.
+pub enum GuttmanQuadraticInsertionStrategy {}
+
+impl InsertionStrategy for GuttmanLinearInsertionStrategy {
+    fn insert<T, Params>(tree: &mut RTree<T, Params>, t: T)
+    where
+        Params: RTreeParams,
+        T: RTreeObject,
+    {
+        let config = *tree.config();
+        insert_with_split(tree.root_mut(), t, &config, choose_subtree, linear_split);
+    }
+}
+
+impl InsertionStrategy for GuttmanQuadraticInsertionStrategy {
+    fn insert<T, Params>(tree: &mut RTree<T, Params>, t: T)
+    where
+        Params: RTreeParams,
+        T: RTreeObject,
+    {
+        let config = *tree.config();
+        insert_with_split(tree.root_mut(), t, &config, choose_subtree, quadratic_split);
+    }
+}
+
+type Scalar<T> = <<<T as RTreeObject>::Envelope as Envelope>::Point as Point>::Scalar;
+
+/// Selects the child of a node that should receive a new envelope.
+///
+/// Returns `None` if the node's children are leaves.
+pub(crate) type ChooseSubtree<T> =
+    fn(&ParentNode<T>, &<T as RTreeObject>::Envelope) -> Option<usize>;
+
+/// Splits an overflowing node and returns the newly created node.
+///
+/// Returns `None` if the node should not be split and keep all of its children.
+pub(crate) type Split<T> = fn(&mut ParentNode<T>, &RTreeConfig) -> Option<RTreeNode<T>>;
+
+/// Inserts an element without performing any reinsertion.
+///
+/// Overflowing nodes are split immediately, a split root increases the tree's height.
+pub(crate) fn insert_with_split<T>(
+    root: &mut ParentNode<T>,
+    t: T,
+    config: &RTreeConfig,
+    choose_subtree: ChooseSubtree<T>,
+    split: Split<T>,
+) where
+    T: RTreeObject,
+{
+    let leaf = RTreeNode::Leaf(t);
+    if let Some(node) = recursive_insert(root, leaf, config, choose_subtree, split) {
+        // The root node was split, create a new root and increase height
+        let old_root = ::std::mem::replace(root, ParentNode::new_root(config));
+        root.envelope = old_root.envelope.merged(&node.envelope());
+        root.children.push(RTreeNode::Parent(old_root));
+        root.children.push(node);
+    }
+}
+
+fn recursive_insert<T>(
+    node: &mut ParentNode<T>,
+    t: RTreeNode<T>,
+    config: &RTreeConfig,
+    choose_subtree: ChooseSubtree<T>,
+    split: Split<T>,
+) -> Option<RTreeNode<T>>
+where
+    T: RTreeObject,
+{
+    node.envelope.merge(&t.envelope());
+    match choose_subtree(node, &t.envelope()) {
+        Some(index) => {
+            if let RTreeNode::Parent(ref mut follow) = node.children[index] {
+                let off_split = recursive_insert(follow, t, config, choose_subtree, split);
+                if let Some(off_split) = off_split {
+                    node.children.push(off_split);
+                }
+            } else {
+                unreachable!("This is a bug in rstar.")
+            }
+        }
+        None => node.children.push(t),
+    }
+    if node.children.len() > config.max_size {
+        split(node, config)
+    } else {
+        None
+    }
+}
+
+/// Returns the child needing the least area enlargement to include an envelope.
+///
+/// Returns `None` if the given node's children are leaves.
+pub(crate) fn choose_subtree<T>(node: &ParentNode<T>, envelope: &T::Envelope) -> Option<usize>
+where
+    T: RTreeObject,
+{
+    match node.children.first() {
+        Some(RTreeNode::Parent(_)) => {}
+        _ => return None,
+    }
+    let mut best = (Scalar::<T>::max_value(), Scalar::<T>::max_value());
+    let mut best_index = 0;
+    for (index, child) in node.children.iter().enumerate() {
+        let child_envelope = child.envelope();
+        let area = child_envelope.area();
+        let enlargement = child_envelope.merged(envelope).area() - area;
+        if (enlargement, area) < best {
+            best = (enlargement, area);
+            best_index = index;
+        }
+    }
+    Some(best_index)
+}
+
+fn linear_split<T>(node: &mut ParentNode<T>, config: &RTreeConfig) -> Option<RTreeNode<T>>
+where
+    T: RTreeObject,
+{
+    let children = ::std::mem::take(&mut node.children);
+    let seeds = linear_seeds(&children);
+    Some(distribute(node, children, seeds, config, |_, _, _| 0))
+}
+
+fn quadratic_split<T>(node: &mut ParentNode<T>, config: &RTreeConfig) -> Option<RTreeNode<T>>
+where
+    T: RTreeObject,
+{
+    let children = ::std::mem::take(&mut node.children);
+    let seeds = quadratic_seeds(&children);
+    Some(distribute(
+        node,
+        children,
+        seeds,
+        config,
+        quadratic_pick_next,
+    ))
+}
+
+/// Returns the pair of children with the greatest normalized separation along any axis.
+///
+/// Along each axis, the separation between the child with the highest low side and the child
+/// with the lowest high side is divided by the width of all children along that axis. This is
+/// Guttman's LinearPickSeeds, the normalization keeps axes with larger coordinate ranges from
+/// being preferred.
+fn linear_seeds<T>(children: &[RTreeNode<T>]) -> (usize, usize)
+where
+    T: RTreeObject,
+{
+    let envelopes: Vec<_> = children.iter().map(RTreeObject::envelope).collect();
+    let mut best_separation = Scalar::<T>::min_value();
+    let mut best_seeds = (0, 1);
+    for axis in 0..<T::Envelope as Envelope>::Point::DIMENSIONS {
+        let mut highest_low = 0;
+        let mut min_low = envelopes[0].lower().nth(axis);
+        let mut max_high = envelopes[0].upper().nth(axis);
+        for (index, envelope) in envelopes.iter().enumerate() {
+            let (low, high) = (envelope.lower().nth(axis), envelope.upper().nth(axis));
+            if low > envelopes[highest_low].lower().nth(axis) {
+                highest_low = index;
+            }
+            if low < min_low {
+                min_low = low;
+            }
+            if high > max_high {
+                max_high = high;
+            }
+        }
+        let width = max_high - min_low;
+        if width <= Zero::zero() {
+            continue;
+        }
+        // The lowest high side is searched among the other children to obtain two seeds
+        let mut lowest_high: Option<usize> = None;
+        for (index, envelope) in envelopes.iter().enumerate() {
+            let high = envelope.upper().nth(axis);
+            if index == highest_low {
+                continue;
+            }
+            match lowest_high {
+                Some(lowest) if envelopes[lowest].upper().nth(axis) <= high => {}
+                _ => lowest_high = Some(index),
+            }
+        }
+        if let Some(lowest_high) = lowest_high {
+            let separation = (envelopes[highest_low].lower().nth(axis)
+                - envelopes[lowest_high].upper().nth(axis))
+                / width;
+            if separation > best_separation {
+                best_separation = separation;
+                best_seeds = (highest_low.min(lowest_high), highest_low.max(lowest_high));
+            }
+        }
+    }
+    best_seeds
+}
+
+/// Returns the pair of children whose combined envelope wastes the most area.
+fn quadratic_seeds<T>(children: &[RTreeNode<T>]) -> (usize, usize)
+where
+    T: RTreeObject,
+{
+    let envelopes: Vec<_> = children.iter().map(RTreeObject::envelope).collect();
+    let mut worst_waste = Scalar::<T>::min_value();
+    let mut seeds = (0, 1);
+    for (i, first) in envelopes.iter().enumerate() {
+        for (j, second) in envelopes.iter().enumerate().skip(i + 1) {
+            let waste = first.merged(second).area() - first.area() - second.area();
+            if waste > worst_waste {
+                worst_waste = waste;
+                seeds = (i, j);
+            }
+        }
+    }
+    seeds
+}
+
+/// Returns the index of the child with the greatest preference for one of both groups.
+fn quadratic_pick_next<T>(
+    remaining: &[RTreeNode<T>],
+    first: &T::Envelope,
+    second: &T::Envelope,
+) -> usize
+where
+    T: RTreeObject,
+{
+    let mut max_difference = Scalar::<T>::min_value();
+    let mut result = 0;
+    for (index, child) in remaining.iter().enumerate() {
+        let envelope = child.envelope();
+        let first_enlargement = first.merged(&envelope).area() - first.area();
+        let second_enlargement = second.merged(&envelope).area() - second.area();
+        let difference = (first_enlargement - second_enlargement).abs();
+        if difference > max_difference {
+            max_difference = difference;
+            result = index;
+        }
+    }
+    result
+}
+
+/// Distributes children into two groups, starting with the given seeds.
+///
+/// The first group is kept in `node`, the second group is returned as a new node.
+fn distribute<T>(
+    node: &mut ParentNode<T>,
+    mut remaining: Vec<RTreeNode<T>>,
+    (first_seed, second_seed): (usize, usize),
+    config: &RTreeConfig,
+    pick_next: impl Fn(&[RTreeNode<T>], &T::Envelope, &T::Envelope) -> usize,
+) -> RTreeNode<T>
+where
+    T: RTreeObject,
+{
+    debug_assert!(first_seed < second_seed);
+    let second_seed = remaining.swap_remove(second_seed);
+    let first_seed = remaining.swap_remove(first_seed);
+    let mut first_envelope = first_seed.envelope();
+    let mut second_envelope = second_seed.envelope();
+    let mut first = Vec::with_capacity(config.max_size + 1);
+    let mut second = Vec::with_capacity(config.max_size + 1);
+    first.push(first_seed);
+    second.push(second_seed);
+
+    while !remaining.is_empty() {
+        if first.len() + remaining.len() <= config.min_size {
+            first.append(&mut remaining);
+            break;
+        }
+        if second.len() + remaining.len() <= config.min_size {
+            second.append(&mut remaining);
+            break;
+        }
+        let next = remaining.swap_remove(pick_next(&remaining, &first_envelope, &second_envelope));
+        let envelope = next.envelope();
+        let first_area = first_envelope.area();
+        let second_area = second_envelope.area();
+        let first_preference = (
+            first_envelope.merged(&envelope).area() - first_area,
+            first_area,
+            first.len(),
+        );
+        let second_preference = (
+            second_envelope.merged(&envelope).area() - second_area,
+            second_area,
+            second.len(),
+        );
+        if first_preference <= second_preference {
+            first_envelope.merge(&envelope);
+            first.push(next);
+        } else {
+            second_envelope.merge(&envelope);
+            second.push(next);
+        }
+    }
+    node.children = first;
+    node.envelope = crate::node::envelope_for_children(&node.children);
+    RTreeNode::Parent(ParentNode::new_parent(second))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{linear_seeds, GuttmanLinearInsertionStrategy, GuttmanQuadraticInsertionStrategy};
+    use crate::node::RTreeNode;
+    use crate::params::RTreeParams;
+    use crate::primitives::Rectangle;
+    use crate::test_utilities::*;
+    use crate::{RTree, RTreeObject};
+
+    struct LinearParams;
+
+    impl RTreeParams for LinearParams {
+        const MIN_SIZE: usize = 3;
+        const MAX_SIZE: usize = 8;
+        const REINSERTION_COUNT: usize = 0;
+        type DefaultInsertionStrategy = GuttmanLinearInsertionStrategy;
+    }
+
+    struct QuadraticParams;
+
+    impl RTreeParams for QuadraticParams {
+        const MIN_SIZE: usize = 2;
+        const MAX_SIZE: usize = 5;
+        const REINSERTION_COUNT: usize = 0;
+        type DefaultInsertionStrategy = GuttmanQuadraticInsertionStrategy;
+    }
+
+    #[test]
+    fn test_linear_insertion() {
+        check_insertion::<LinearParams, _>(create_random_points(1000, SEED_1));
+        check_insertion::<LinearParams, _>(create_random_rectangles(1000, SEED_1));
+        check_insertion::<LinearParams, _>(vec![[1, 1]; 100]);
+    }
+
+    #[test]
+    fn test_linear_seeds_are_normalized() {
+        // The separation along the x axis is larger but covers a smaller part of its width
+        let children: Vec<_> = vec![
+            Rectangle::from_corners([0.0, 0.0], [400.0, 1.0]),
+            Rectangle::from_corners([600.0, 0.0], [1000.0, 1.0]),
+            Rectangle::from_corners([450.0, 9.0], [550.0, 10.0]),
+        ]
+        .into_iter()
+        .map(RTreeNode::Leaf)
+        .collect();
+        assert_eq!(linear_seeds(&children), (0, 2));
+    }
+
+    #[test]
+    fn test_quadratic_insertion() {
+        check_insertion::<QuadraticParams, _>(create_random_points(1000, SEED_1));
+        check_insertion::<QuadraticParams, _>(create_random_rectangles(1000, SEED_1));
+        check_insertion::<QuadraticParams, _>(vec![[1, 1]; 100]);
+    }
+
+    fn check_insertion<Params, T>(elements: Vec<T>)
+    where
+        Params: RTreeParams,
+        T: RTreeObject + PartialEq + Clone,
+    {
+        let mut tree: RTree<T, Params> = RTree::new_with_params();
+        for element in &elements {
+            tree.insert(element.clone());
+            tree.root().sanity_check::<Params>(true);
+        }
+        assert_eq!(tree.size(), elements.len());
+        for element in &elements {
+            assert!(tree.contains(element));
+        }
+        for element in &elements {
+            assert!(tree.remove(element).is_some());
+        }
+        assert_eq!(tree.size(), 0);
+    }
+}
@@ -0,0 +1,589 @@
+use crate::algorithm::bulk_load::div_up;
+use crate::envelope::Envelope;
+use crate::node::{envelope_for_children, ParentNode, RTreeNode};
+use crate::object::RTreeObject;
+use crate::params::{InsertionStrategy, RTreeConfig, RTreeParams};
+use crate::point::{Point, PointExt};
+use crate::rtree::RTree;
+use num_traits::{Bounded, One, Zero};
+use smallvec::{smallvec, SmallVec};
+
+/// Inserts elements according to the Hilbert r-tree heuristic.
+///
+/// All elements are ordered by the Hilbert value of their envelope's center. The children of
+/// each node are kept in this order, a subtree's position is given by the largest Hilbert value
+/// (LHV) of its elements. A new element descends into the first child whose LHV is not smaller
+/// than its own Hilbert value and is inserted at its position in this order.
+///
+/// Overflowing nodes are not split immediately. Instead, their children are redistributed among
+/// the node and its neighboring siblings in Hilbert order (up to two cooperating siblings). Only
+/// if all of these nodes are full, a new node is created and all children are redistributed
+/// evenly, turning three full nodes into four. This keeps nodes considerably fuller than
+/// [R* insertion](crate::RStarInsertionStrategy), reducing the memory footprint of large trees
+/// at the cost of some query performance.
+///
+/// Since envelopes may use arbitrary scalar types, Hilbert values are calculated within a frame
+/// derived from the tree's envelope. The frame is rounded to powers of two and only changes if
+/// the tree's extent changes considerably. The tree is then reorganized in `O(n * log(n))`, as
+/// is a tree whose order has been changed by other operations, e.g. [RTree::insert_bulk].
+/// `RTreeParams::REINSERTION_COUNT` is ignored by this strategy.
+///
+/// # References
+/// [Kamel, Ibrahim, and Christos Faloutsos. "Hilbert R-tree: An improved R-tree using fractals." Proceedings of the 20th International Conference on Very Large Data Bases. 1994.](http://www.vldb.org/conf/1994/P500.PDF)
+///
+/// # Example
+/// ```
+/// use rstar::{RTree, RTreeParams, HilbertInsertionStrategy};
+///
+/// struct HilbertParams;
+///
+/// impl RTreeParams for HilbertParams {
+///     const MIN_SIZE: usize = 6;
+///     const MAX_SIZE: usize = 16;
+///     const REINSERTION_COUNT: usize = 0;
+///     type DefaultInsertionStrategy = HilbertInsertionStrategy;
+/// }
+///
+/// let mut tree: RTree<_, HilbertParams> = RTree::new_with_params();
+/// tree.insert([0.0, 1.0]);
+/// assert_eq!(tree.size(), 1);
+/// ```
+pub enum HilbertInsertionStrategy {}
+
+impl InsertionStrategy for HilbertInsertionStrategy {
+    fn insert<T, Params>(tree: &mut RTree<T, Params>, t: T)
+    where
+        Params: RTreeParams,
+        T: RTreeObject,
+    {
+        let config = *tree.config();
+        let root = tree.root_mut();
+        let frame = HilbertFrame::new(&root.envelope.merged(&t.envelope()));
+        let result = if !root.children.is_empty() && HilbertFrame::new(&root.envelope) == frame {
+            insert_in_order(root, t, &frame, &config)
+        } else {
+            Err(t)
+        };
+        match result {
+            Ok(()) => {
+                if root.children.len() > config.max_size {
+                    let old_root = ::std::mem::replace(root, ParentNode::new_root(&config));
+                    root.envelope = old_root.envelope;
+                    root.children.push(RTreeNode::Parent(old_root));
+                    resolve_overflow(root, 0, &config);
+                }
+            }
+            Err(t) => reorganize(root, t, &frame, &config),
+        }
+    }
+}
+
+/// The box in which Hilbert values are calculated.
+#[derive(PartialEq)]
+struct HilbertFrame<P> {
+    lower: P,
+    upper: P,
+}
+
+impl<P: Point> HilbertFrame<P> {
+    /// Creates a frame containing the centers of all elements within `envelope`.
+    ///
+    /// Each axis spans four times the smallest power of two that is not smaller than the
+    /// envelope's perimeter value, aligned to a multiple of this power of two. Hence the frame
+    /// does not change if the envelope is only slightly enlarged.
+    fn new<E: Envelope<Point = P>>(envelope: &E) -> Self {
+        let one = P::Scalar::one();
+        let two = one + one;
+        let extent = envelope.perimeter_value();
+        let mut size = one;
+        while size < extent && size < P::Scalar::max_value() / (two * two) {
+            size = size * two;
+        }
+        loop {
+            let half = size / two;
+            if half < extent || half == P::Scalar::zero() || half == size {
+                break;
+            }
+            size = half;
+        }
+        let center = envelope.center();
+        let base = center.map(|value| {
+            let remainder = value % size;
+            if remainder < P::Scalar::zero() {
+                value - remainder - size
+            } else {
+                value - remainder
+            }
+        });
+        HilbertFrame {
+            lower: base.map(|value| {
+                if value < P::Scalar::min_value() + size + size {
+                    P::Scalar::min_value()
+                } else {
+                    value - size - size
+                }
+            }),
+            upper: base.map(|value| {
+                if value > P::Scalar::max_value() - size - size {
+                    P::Scalar::max_value()
+                } else {
+                    value + size + size
+                }
+            }),
+        }
+    }
+
+    fn key(&self, point: &P) -> u64 {
+        let mut coordinates: SmallVec<[u64; 4]> = smallvec![0; P::DIMENSIONS];
+        hilbert_key(point, &self.lower, &self.upper, &mut coordinates)
+    }
+
+    /// Returns the largest Hilbert value of all elements within `node`.
+    ///
+    /// Since all children are ordered, this is the Hilbert value of the last element.
+    fn largest_hilbert_value<T>(&self, node: &RTreeNode<T>) -> u64
+    where
+        T: RTreeObject,
+        T::Envelope: Envelope<Point = P>,
+    {
+        let mut current = node;
+        loop {
+            match current {
+                RTreeNode::Leaf(ref t) => return self.key(&t.envelope().center()),
+                RTreeNode::Parent(ref data) => match data.children.last() {
+                    Some(last) => current = last,
+                    None => return 0,
+                },
+            }
+        }
+    }
+}
+
+/// Inserts `t` at its position in Hilbert order.
+///
+/// Returns `t` without modifying the tree if the children along its path are not ordered
+/// within `frame`.
+fn insert_in_order<T>(
+    node: &mut ParentNode<T>,
+    t: T,
+    frame: &HilbertFrame<<T::Envelope as Envelope>::Point>,
+    config: &RTreeConfig,
+) -> Result<(), T>
+where
+    T: RTreeObject,
+{
+    let envelope = t.envelope();
+    let key = frame.key(&envelope.center());
+    let child_keys: Vec<_> = node
+        .children
+        .iter()
+        .map(|child| frame.largest_hilbert_value(child))
+        .collect();
+    if child_keys.windows(2).any(|pair| pair[0] > pair[1]) {
+        return Err(t);
+    }
+
+    if let Some(RTreeNode::Parent(_)) = node.children.first() {
+        // Descend into the first child whose LHV is not smaller than the new key, or into the
+        // last child if there is no such child.
+        let index = child_keys
+            .iter()
+            .position(|child_key| *child_key >= key)
+            .unwrap_or(child_keys.len() - 1);
+        if let RTreeNode::Parent(ref mut child) = node.children[index] {
+            insert_in_order(child, t, frame, config)?;
+            if child.children.len() > config.max_size {
+                resolve_overflow(node, index, config);
+            }
+        }
+    } else {
+        let position = child_keys
+            .iter()
+            .position(|child_key| *child_key > key)
+            .unwrap_or(child_keys.len());
+        node.children.insert(position, RTreeNode::Leaf(t));
+    }
+    node.envelope.merge(&envelope);
+    Ok(())
+}
+
+/// Resolves the overflow of `node.children[index]` by redistributing its children among the
+/// child and its cooperating siblings. A new sibling is created if all of these are full.
+fn resolve_overflow<T>(node: &mut ParentNode<T>, index: usize, config: &RTreeConfig)
+where
+    T: RTreeObject,
+{
+    let first = index.saturating_sub(1);
+    let last = (index + 1).min(node.children.len() - 1);
+    // Siblings cover consecutive ranges of the Hilbert order, their children remain ordered
+    let mut entries = Vec::new();
+    for sibling in node.children.drain(first..=last) {
+        match sibling {
+            RTreeNode::Parent(data) => entries.extend(data.children),
+            RTreeNode::Leaf(_) => unreachable!("This is a bug."),
+        }
+    }
+
+    let mut node_count = last - first + 1;
+    if entries.len() > node_count * config.max_size {
+        node_count += 1;
+    }
+    let new_nodes = distribute(entries, node_count);
+    node.children.splice(first..first, new_nodes);
+}
+
+/// Sorts all elements of the tree and `t` in Hilbert order and packs them into a new tree.
+fn reorganize<T>(
+    root: &mut ParentNode<T>,
+    t: T,
+    frame: &HilbertFrame<<T::Envelope as Envelope>::Point>,
+    config: &RTreeConfig,
+) where
+    T: RTreeObject,
+{
+    let mut elements = Vec::new();
+    collect_elements(
+        ::std::mem::replace(root, ParentNode::new_root(config)),
+        &mut elements,
+    );
+    elements.push(t);
+    let mut keyed_elements: Vec<_> = elements
+        .into_iter()
+        .map(|element| (frame.key(&element.envelope().center()), element))
+        .collect();
+    keyed_elements.sort_by_key(|&(key, _)| key);
+
+    let mut nodes: Vec<_> = keyed_elements
+        .into_iter()
+        .map(|(_, element)| RTreeNode::Leaf(element))
+        .collect();
+    while nodes.len() > config.max_size {
+        let node_count = div_up(nodes.len(), config.max_size);
+        nodes = distribute(nodes, node_count);
+    }
+    root.children.extend(nodes);
+    root.envelope = envelope_for_children(&root.children);
+}
+
+fn collect_elements<T>(node: ParentNode<T>, elements: &mut Vec<T>)
+where
+    T: RTreeObject,
+{
+    for child in node.children {
+        match child {
+            RTreeNode::Leaf(t) => elements.push(t),
+            RTreeNode::Parent(data) => collect_elements(data, elements),
+        }
+    }
+}
+
+/// Distributes consecutive runs of `entries` evenly among `node_count` new nodes.
+fn distribute<T>(entries: Vec<RTreeNode<T>>, node_count: usize) -> Vec<RTreeNode<T>>
+where
+    T: RTreeObject,
+{
+    let total = entries.len();
+    let mut entries = entries.into_iter();
+    (0..node_count)
+        .map(|n| {
+            let size = (n + 1) * total / node_count - n * total / node_count;
+            RTreeNode::Parent(ParentNode::new_parent(
+                entries.by_ref().take(size).collect(),
+            ))
+        })
+        .collect()
+}
+
+/// Calculates the Hilbert values of a set of points.
+///
+/// The points are mapped onto a grid spanning their bounding box. The resulting values are only
+/// comparable with each other.
+pub(crate) fn hilbert_keys<P: Point>(points: &[P]) -> Vec<u64> {
+    let first = match points.first() {
+        Some(first) => *first,
+        None => return Vec::new(),
+    };
+    let (lower, upper) = points.iter().fold((first, first), |(lower, upper), point| {
+        (lower.min_point(point), upper.max_point(point))
+    });
+    hilbert_keys_in_frame(points, &lower, &upper)
+}
+
+/// Calculates the Hilbert values of a set of points within the box spanned by `lower` and
+/// `upper`.
+///
+/// All points must be contained in the box. Values calculated with the same box are comparable
+/// with each other.
+pub(crate) fn hilbert_keys_in_frame<P: Point>(points: &[P], lower: &P, upper: &P) -> Vec<u64> {
+    let mut coordinates = vec![0u64; P::DIMENSIONS];
+    points
+        .iter()
+        .map(|point| hilbert_key(point, lower, upper, &mut coordinates))
+        .collect()
+}
+
+/// Calculates the Hilbert value of a single point, `coordinates` is used as scratch space.
+///
+/// Points outside of the box spanned by `lower` and `upper` are mapped onto the closest cell at
+/// the box's boundary.
+pub(crate) fn hilbert_key<P: Point>(
+    point: &P,
+    lower: &P,
+    upper: &P,
+    coordinates: &mut [u64],
+) -> u64 {
+    let bits = (64 / P::DIMENSIONS).min(32) as u32;
+    for (axis, coordinate) in coordinates.iter_mut().enumerate() {
+        *coordinate = quantize(point.nth(axis), lower.nth(axis), upper.nth(axis), bits);
+    }
+    hilbert_index(coordinates, bits)
+}
+
+/// Maps `value` onto a grid with `2^bits` cells spanning `[lower, upper]`.
+///
+/// The grid cells are determined by repeated bisection, requiring nothing but basic arithmetic
+/// of the scalar type.
+fn quantize<S: crate::RTreeNum>(value: S, mut lower: S, mut upper: S, bits: u32) -> u64 {
+    let two = S::one() + S::one();
+    let mut result = 0;
+    for _ in 0..bits {
+        let mut mid = lower + (upper - lower) / two;
+        if mid == lower {
+            // Integer scalars can not be bisected any further
+            mid = upper;
+        }
+        result <<= 1;
+        if value >= mid {
+            result |= 1;
+            lower = mid;
+        } else {
+            upper = mid;
+        }
+    }
+    result
+}
+
+/// Calculates the position of a grid cell along the Hilbert curve.
+///
+/// Uses Skilling's algorithm ("Programming the Hilbert curve", 2004) which works for an
+/// arbitrary number of dimensions. `coordinates` is used as scratch space.
+fn hilbert_index(coordinates: &mut [u64], bits: u32) -> u64 {
+    let dimensions = coordinates.len();
+    let m = 1u64 << (bits - 1);
+
+    // Inverse undo excess work
+    let mut q = m;
+    while q > 1 {
+        let p = q - 1;
+        for i in 0..dimensions {
+            if coordinates[i] & q != 0 {
+                coordinates[0] ^= p;
+            } else {
+                let t = (coordinates[0] ^ coordinates[i]) & p;
+                coordinates[0] ^= t;
+                coordinates[i] ^= t;
+            }
+        }
+        q >>= 1;
+    }
+
+    // Gray encode
+    for i in 1..dimensions {
+        coordinates[i] ^= coordinates[i - 1];
+    }
+    let mut t = 0;
+    let mut q = m;
+    while q > 1 {
+        if coordinates[dimensions - 1] & q != 0 {
+            t ^= q - 1;
+        }
+        q >>= 1;
+    }
+    for coordinate in coordinates.iter_mut() {
+        *coordinate ^= t;
+    }
+
+    // Interleave the transposed bits, most significant bits first
+    let mut index = 0;
+    for bit in (0..bits).rev() {
+        for coordinate in coordinates.iter() {
+            index = (index << 1) | ((coordinate >> bit) & 1);
+        }
+    }
+    index
+}
+
+#[cfg(test)]
+mod test {
+    use super::{hilbert_keys, HilbertFrame, HilbertInsertionStrategy};
+    use crate::algorithm::iterators::RTreeIterator;
+    use crate::algorithm::selection_functions::SelectAllFunc;
+    use crate::node::{ParentNode, RTreeNode};
+    use crate::params::RTreeParams;
+    use crate::test_utilities::*;
+    use crate::{RStarInsertionStrategy, RTree, RTreeObject};
+
+    struct HilbertParams;
+
+    impl RTreeParams for HilbertParams {
+        const MIN_SIZE: usize = 3;
+        const MAX_SIZE: usize = 8;
+        const REINSERTION_COUNT: usize = 0;
+        type DefaultInsertionStrategy = HilbertInsertionStrategy;
+    }
+
+    struct RStarParams;
+
+    impl RTreeParams for RStarParams {
+        const MIN_SIZE: usize = 3;
+        const MAX_SIZE: usize = 8;
+        const REINSERTION_COUNT: usize = 2;
+        type DefaultInsertionStrategy = RStarInsertionStrategy;
+    }
+
+    #[test]
+    fn test_hilbert_keys_visit_neighboring_cells() {
+        let grid_2d: Vec<_> = (0..64)
+            .map(|i| [(i % 8) as f64 + 0.5, (i / 8) as f64 + 0.5])
+            .collect();
+        check_neighboring_cells(&grid_2d);
+        let grid_3d: Vec<_> = (0..64)
+            .map(|i| {
+                [
+                    (i % 4) as f64 + 0.5,
+                    (i / 4 % 4) as f64 + 0.5,
+                    (i / 16) as f64 + 0.5,
+                ]
+            })
+            .collect();
+        check_neighboring_cells(&grid_3d);
+    }
+
+    fn check_neighboring_cells<P: crate::Point<Scalar = f64>>(grid: &[P]) {
+        let keys = hilbert_keys(grid);
+        let mut sorted: Vec<_> = keys.iter().zip(grid).collect();
+        sorted.sort_by_key(|&(key, _)| *key);
+        for window in sorted.windows(2) {
+            assert_ne!(window[0].0, window[1].0);
+            let distance: f64 = (0..P::DIMENSIONS)
+                .map(|axis| (window[0].1.nth(axis) - window[1].1.nth(axis)).abs())
+                .sum();
+            assert_eq!(distance, 1.0);
+        }
+    }
+
+    #[test]
+    fn test_hilbert_insertion() {
+        check_insertion(create_random_points(1000, SEED_1));
+        check_insertion(create_random_rectangles(1000, SEED_1));
+        check_insertion(vec![[1, 1]; 100]);
+    }
+
+    #[test]
+    fn test_hilbert_insertion_with_growing_extent() {
+        let points: Vec<_> = (0..1000)
+            .map(|i| [i as f64 * 0.5, (i % 7) as f64])
+            .collect();
+        check_insertion(points);
+        let points: Vec<_> = (0..1000).map(|i| [-i, i * i]).collect();
+        check_insertion(points);
+    }
+
+    #[test]
+    fn test_hilbert_insertion_restores_order() {
+        let mut tree: RTree<_, HilbertParams> = RTree::new_with_params();
+        let points = create_random_points(500, SEED_1);
+        for point in &points[..250] {
+            tree.insert(*point);
+        }
+        // Bulk insertion does not keep the Hilbert order
+        tree.insert_bulk(points[250..].to_vec());
+        tree.insert([0.5, 0.5]);
+        check_hilbert_order(&tree);
+        assert_eq!(tree.size(), 501);
+    }
+
+    fn check_insertion<T>(elements: Vec<T>)
+    where
+        T: RTreeObject + PartialEq + Clone,
+    {
+        let mut tree: RTree<T, HilbertParams> = RTree::new_with_params();
+        for element in &elements {
+            tree.insert(element.clone());
+            tree.root().sanity_check::<HilbertParams>(true);
+        }
+        check_hilbert_order(&tree);
+        assert_eq!(tree.size(), elements.len());
+        assert_eq!(
+            RTreeIterator::new(tree.root(), SelectAllFunc).count(),
+            elements.len()
+        );
+        for element in &elements {
+            assert!(tree.contains(element));
+        }
+        for (index, element) in elements.iter().enumerate() {
+            assert!(tree.remove(element).is_some());
+            if index % 10 == 0 {
+                tree.insert(element.clone());
+                assert!(tree.remove(element).is_some());
+            }
+        }
+        assert_eq!(tree.size(), 0);
+    }
+
+    /// Checks that all elements are ordered by their Hilbert value within the tree's frame.
+    fn check_hilbert_order<T, Params>(tree: &RTree<T, Params>)
+    where
+        T: RTreeObject,
+        Params: RTreeParams,
+    {
+        let frame = HilbertFrame::new(&tree.root().envelope());
+        let mut keys = Vec::new();
+        collect_keys(tree.root(), &frame, &mut keys);
+        assert_eq!(keys.len(), tree.size());
+        assert!(keys.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    fn collect_keys<T: RTreeObject>(
+        node: &ParentNode<T>,
+        frame: &HilbertFrame<<T::Envelope as crate::Envelope>::Point>,
+        keys: &mut Vec<u64>,
+    ) {
+        for child in &node.children {
+            match child {
+                RTreeNode::Leaf(_) => keys.push(frame.largest_hilbert_value(child)),
+                RTreeNode::Parent(ref data) => collect_keys(data, frame, keys),
+            }
+        }
+    }
+
+    #[test]
+    fn test_hilbert_node_utilization() {
+        let points = create_random_points(2000, SEED_1);
+        let mut hilbert_tree: RTree<_, HilbertParams> = RTree::new_with_params();
+        let mut rstar_tree: RTree<_, RStarParams> = RTree::new_with_params();
+        for point in &points {
+            hilbert_tree.insert(*point);
+            rstar_tree.insert(*point);
+        }
+        let hilbert_nodes = count_nodes(hilbert_tree.root());
+        let rstar_nodes = count_nodes(rstar_tree.root());
+        assert!(hilbert_nodes < rstar_nodes);
+        // Splitting a single node into two yields about 70% utilization and splitting two
+        // nodes into three about 80%. Deferred splitting with two cooperating siblings keeps
+        // all nodes but the root at least 85% full on average.
+        let entries = points.len() + hilbert_nodes - 1;
+        assert!(entries * 20 >= (hilbert_nodes - 1) * HilbertParams::MAX_SIZE * 17);
+    }
+
+    fn count_nodes<T: RTreeObject>(node: &ParentNode<T>) -> usize {
+        1 + node
+            .children
+            .iter()
+            .map(|child| match child {
+                RTreeNode::Parent(ref data) => count_nodes(data),
+                RTreeNode::Leaf(_) => 0,
+            })
+            .sum::<usize>()
+    }
+}
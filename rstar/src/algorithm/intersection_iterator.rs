@@ -55,6 +55,45 @@ where
             }
         }
     }
+
+    fn expand(&mut self, node1: &'a RTreeNode<T>, node2: &'a RTreeNode<U>) {
+        match (node1, node2) {
+            (Leaf(_), Leaf(_)) => unreachable!("A pair of leaves can not be expanded"),
+            (leaf @ Leaf(_), Parent(p)) => {
+                p.children()
+                    .iter()
+                    .for_each(|c| self.push_if_intersecting(leaf, c));
+            }
+            (Parent(p), leaf @ Leaf(_)) => {
+                p.children()
+                    .iter()
+                    .for_each(|c| self.push_if_intersecting(c, leaf));
+            }
+            (Parent(p1), Parent(p2)) => {
+                self.add_intersecting_children(p1, p2);
+            }
+        }
+    }
+
+    /// Splits off about half of the remaining node pairs into a new iterator.
+    ///
+    /// A single remaining pair of nodes is expanded until it can be split. Returns `None` if
+    /// only a single pair of leaves or no pair is left.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn split_off(&mut self) -> Option<Self> {
+        while let [(node1, node2)] = self.todo_list[..] {
+            if let (Leaf(_), Leaf(_)) = (node1, node2) {
+                break;
+            }
+            self.todo_list.clear();
+            self.expand(node1, node2);
+        }
+        if self.todo_list.len() < 2 {
+            return None;
+        }
+        let todo_list = self.todo_list.split_off(self.todo_list.len() / 2);
+        Some(IntersectionIterator { todo_list })
+    }
 }
 
 impl<'a, T, U> Iterator for IntersectionIterator<'a, T, U>
@@ -68,19 +107,7 @@ where
         while let Some(next) = self.todo_list.pop() {
             match next {
                 (Leaf(t1), Leaf(t2)) => return Some((&t1, &t2)),
-                (leaf @ Leaf(_), Parent(p)) => {
-                    p.children()
-                        .iter()
-                        .for_each(|c| self.push_if_intersecting(leaf, c));
-                }
-                (Parent(p), leaf @ Leaf(_)) => {
-                    p.children()
-                        .iter()
-                        .for_each(|c| self.push_if_intersecting(c, leaf));
-                }
-                (Parent(p1), Parent(p2)) => {
-                    self.add_intersecting_children(p1, p2);
-                }
+                (node1, node2) => self.expand(node1, node2),
             }
         }
         None
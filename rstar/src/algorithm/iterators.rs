@@ -125,6 +125,55 @@ where
     }
 }
 
+/// Iterator that moves all elements out of an r-tree.
+pub struct RTreeIntoIterator<T>
+where
+    T: RTreeObject,
+{
+    current_nodes: Vec<RTreeNode<T>>,
+    remaining: usize,
+}
+
+impl<T> RTreeIntoIterator<T>
+where
+    T: RTreeObject,
+{
+    pub(crate) fn new(root: ParentNode<T>, size: usize) -> Self {
+        RTreeIntoIterator {
+            current_nodes: root.children,
+            remaining: size,
+        }
+    }
+}
+
+impl<T> Iterator for RTreeIntoIterator<T>
+where
+    T: RTreeObject,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while let Some(next) = self.current_nodes.pop() {
+            match next {
+                RTreeNode::Leaf(t) => {
+                    self.remaining -= 1;
+                    return Some(t);
+                }
+                RTreeNode::Parent(data) => {
+                    self.current_nodes.extend(data.children);
+                }
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for RTreeIntoIterator<T> where T: RTreeObject {}
+
 #[cfg(test)]
 mod test {
     use crate::aabb::AABB;
@@ -260,6 +309,40 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_into_iter() {
+        const NUM_POINTS: usize = 1000;
+        let points = create_random_points(NUM_POINTS, SEED_1);
+        let tree = RTree::bulk_load(points.clone());
+        let mut iterator = tree.into_iter();
+        assert_eq!(iterator.len(), NUM_POINTS);
+        iterator.next();
+        assert_eq!(iterator.len(), NUM_POINTS - 1);
+
+        let mut tree: RTree<_> = points.iter().cloned().collect();
+        assert_eq!(tree.size(), NUM_POINTS);
+        let mut drained: Vec<_> = tree.drain().collect();
+        assert_eq!(tree.size(), 0);
+        assert_eq!(tree.iter().count(), 0);
+        tree.insert([0.5, 0.5]);
+        assert_eq!(tree.size(), 1);
+
+        let mut expected = points;
+        drained.sort_by(|l, r| l.partial_cmp(r).unwrap());
+        expected.sort_by(|l, r| l.partial_cmp(r).unwrap());
+        assert_eq!(drained, expected);
+    }
+
+    #[test]
+    fn test_extend() {
+        let points = create_random_points(500, SEED_1);
+        let mut tree = RTree::new();
+        tree.extend(points[..100].iter().cloned());
+        tree.extend(points[100..].iter().cloned());
+        assert_eq!(tree.size(), 500);
+        assert!(points.iter().all(|p| tree.contains(p)));
+    }
+
     #[test]
     fn test_locate_within_distance() {
         use crate::primitives::Line;
@@ -1,7 +1,15 @@
 pub mod bulk_load;
+pub mod graft;
+pub mod guttman;
+pub mod hilbert;
 pub mod intersection_iterator;
 pub mod iterators;
 pub mod nearest_neighbor;
+pub mod nearest_neighbor_batch;
+#[cfg(feature = "rayon")]
+pub mod parallel_iterators;
 pub mod removal;
+pub mod rrstar;
 pub mod rstar;
 pub mod selection_functions;
+pub mod xtree;
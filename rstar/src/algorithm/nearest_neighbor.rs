@@ -5,12 +5,12 @@ use heapless::binary_heap as static_heap;
 use num_traits::Bounded;
 use std::collections::binary_heap::BinaryHeap;
 
-struct RTreeNodeDistanceWrapper<'a, T>
+pub(crate) struct RTreeNodeDistanceWrapper<'a, T>
 where
     T: PointDistance + 'a,
 {
-    node: &'a RTreeNode<T>,
-    distance: <<T::Envelope as Envelope>::Point as Point>::Scalar,
+    pub(crate) node: &'a RTreeNode<T>,
+    pub(crate) distance: <<T::Envelope as Envelope>::Point as Point>::Scalar,
 }
 
 impl<'a, T> PartialEq for RTreeNodeDistanceWrapper<'a, T>
@@ -0,0 +1,227 @@
+use crate::algorithm::hilbert::hilbert_keys;
+use crate::algorithm::nearest_neighbor::RTreeNodeDistanceWrapper;
+use crate::node::{ParentNode, RTreeNode};
+use crate::point::{max_inline, Point};
+use crate::{Envelope, PointDistance};
+use num_traits::{Bounded, Zero};
+use std::collections::binary_heap::BinaryHeap;
+
+/// The number of consecutive queries that are answered by the same thread.
+#[cfg(feature = "rayon")]
+const PARALLEL_CHUNK_SIZE: usize = 256;
+
+/// Answers k nearest neighbor queries one after another.
+///
+/// The heap is reused between queries and the result of the previous query is used to bound
+/// the search radius of the next query.
+struct BatchSearch<'a, T>
+where
+    T: PointDistance + 'a,
+{
+    root: &'a ParentNode<T>,
+    k: usize,
+    nodes: BinaryHeap<RTreeNodeDistanceWrapper<'a, T>>,
+    previous: Vec<&'a T>,
+}
+
+impl<'a, T> BatchSearch<'a, T>
+where
+    T: PointDistance,
+{
+    fn new(root: &'a ParentNode<T>, k: usize) -> Self {
+        BatchSearch {
+            root,
+            k,
+            nodes: BinaryHeap::with_capacity(20),
+            previous: Vec::with_capacity(k),
+        }
+    }
+
+    fn query(&mut self, query_point: &<T::Envelope as Envelope>::Point) -> Vec<&'a T> {
+        if self.k == 0 {
+            return Vec::new();
+        }
+        // The previous query's k neighbors are all within this distance, hence the k-th
+        // nearest neighbor can not be any further away
+        let max_distance = if self.previous.len() == self.k {
+            self.previous
+                .iter()
+                .map(|t| t.distance_2(query_point))
+                .fold(Zero::zero(), max_inline)
+        } else {
+            Bounded::max_value()
+        };
+        self.nodes.clear();
+        extend_heap(&mut self.nodes, self.root, query_point, max_distance);
+        let mut result = Vec::with_capacity(self.k);
+        while let Some(current) = self.nodes.pop() {
+            match current.node {
+                RTreeNode::Parent(ref data) => {
+                    extend_heap(&mut self.nodes, data, query_point, max_distance);
+                }
+                RTreeNode::Leaf(ref t) => {
+                    result.push(t);
+                    if result.len() == self.k {
+                        break;
+                    }
+                }
+            }
+        }
+        self.previous.clone_from(&result);
+        result
+    }
+}
+
+fn extend_heap<'a, T>(
+    nodes: &mut BinaryHeap<RTreeNodeDistanceWrapper<'a, T>>,
+    node: &'a ParentNode<T>,
+    query_point: &<T::Envelope as Envelope>::Point,
+    max_distance: <<T::Envelope as Envelope>::Point as Point>::Scalar,
+) where
+    T: PointDistance,
+{
+    nodes.extend(node.children.iter().filter_map(|child| {
+        let distance = match child {
+            RTreeNode::Parent(ref data) => {
+                let distance = data.envelope.distance_2(query_point);
+                if distance <= max_distance {
+                    Some(distance)
+                } else {
+                    None
+                }
+            }
+            RTreeNode::Leaf(ref t) => t.distance_2_if_less_or_equal(query_point, max_distance),
+        }?;
+        Some(RTreeNodeDistanceWrapper {
+            node: child,
+            distance,
+        })
+    }));
+}
+
+/// Returns the indices of the query points in the order in which they should be answered.
+fn query_order<P: Point>(query_points: &[P]) -> Vec<usize> {
+    let keys = hilbert_keys(query_points);
+    let mut order: Vec<_> = (0..query_points.len()).collect();
+    order.sort_by_key(|index| keys[*index]);
+    order
+}
+
+pub fn nearest_neighbors_batch<'a, T>(
+    root: &'a ParentNode<T>,
+    query_points: &[<T::Envelope as Envelope>::Point],
+    k: usize,
+) -> Vec<Vec<&'a T>>
+where
+    T: PointDistance,
+{
+    let mut result = vec![Vec::new(); query_points.len()];
+    let mut search = BatchSearch::new(root, k);
+    for index in query_order(query_points) {
+        result[index] = search.query(&query_points[index]);
+    }
+    result
+}
+
+#[cfg(feature = "rayon")]
+pub fn par_nearest_neighbors_batch<'a, T>(
+    root: &'a ParentNode<T>,
+    query_points: &[<T::Envelope as Envelope>::Point],
+    k: usize,
+) -> Vec<Vec<&'a T>>
+where
+    T: PointDistance + Sync,
+    T::Envelope: Sync,
+    <T::Envelope as Envelope>::Point: Sync,
+{
+    use rayon::prelude::*;
+
+    let order = query_order(query_points);
+    let chunks: Vec<Vec<_>> = order
+        .par_chunks(PARALLEL_CHUNK_SIZE)
+        .map(|chunk| {
+            let mut search = BatchSearch::new(root, k);
+            chunk
+                .iter()
+                .map(|index| search.query(&query_points[*index]))
+                .collect()
+        })
+        .collect();
+    let mut result = vec![Vec::new(); query_points.len()];
+    for (index, neighbors) in order.into_iter().zip(chunks.into_iter().flatten()) {
+        result[index] = neighbors;
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use crate::object::PointDistance;
+    use crate::rtree::RTree;
+    use crate::test_utilities::*;
+
+    #[test]
+    fn test_nearest_neighbors_batch() {
+        let points = create_random_points(1000, SEED_1);
+        let tree = RTree::bulk_load(points);
+        let query_points = create_random_points(300, SEED_2);
+        for &k in &[0, 1, 2, 7, 30] {
+            let result = tree.nearest_neighbors_batch(&query_points, k);
+            assert_eq!(result.len(), query_points.len());
+            for (query_point, neighbors) in query_points.iter().zip(&result) {
+                let expected: Vec<_> = tree.nearest_neighbor_iter(query_point).take(k).collect();
+                assert_eq!(neighbors.len(), k);
+                let distances = |elements: &[&[f64; 2]]| -> Vec<f64> {
+                    elements
+                        .iter()
+                        .map(|element| element.distance_2(query_point))
+                        .collect()
+                };
+                assert_eq!(distances(neighbors), distances(&expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_nearest_neighbors_batch_with_small_tree() {
+        let tree = RTree::bulk_load(vec![[0.0, 0.0], [1.0, 0.0], [5.0, 5.0]]);
+        let result = tree.nearest_neighbors_batch(&[[4.0, 4.0], [0.1, 0.0], [4.0, 4.0]], 5);
+        assert_eq!(result[0], vec![&[5.0, 5.0], &[1.0, 0.0], &[0.0, 0.0]]);
+        assert_eq!(result[1], vec![&[0.0, 0.0], &[1.0, 0.0], &[5.0, 5.0]]);
+        assert_eq!(result[2], result[0]);
+
+        let empty: RTree<[f64; 2]> = RTree::new();
+        assert_eq!(
+            empty.nearest_neighbors_batch(&[[0.0, 0.0]], 3),
+            vec![Vec::<&[f64; 2]>::new()]
+        );
+        assert!(tree.nearest_neighbors_batch(&[], 3).is_empty());
+    }
+
+    #[test]
+    fn test_nearest_neighbors_batch_with_duplicate_distances() {
+        let grid: Vec<_> = (0..400).map(|i| [i % 20, i / 20]).collect();
+        let tree = RTree::bulk_load(grid);
+        let query_points: Vec<_> = (0..100)
+            .map(|i| [(i * 7) % 18 + 1, (i * 3) % 18 + 1])
+            .collect();
+        let result = tree.nearest_neighbors_batch(&query_points, 5);
+        for (query_point, neighbors) in query_points.iter().zip(&result) {
+            assert_eq!(neighbors[0], query_point);
+            assert!(neighbors[1..]
+                .iter()
+                .all(|neighbor| neighbor.distance_2(query_point) == 1));
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_nearest_neighbors_batch() {
+        let tree = RTree::bulk_load(create_random_points(1000, SEED_1));
+        let query_points = create_random_points(2000, SEED_2);
+        assert_eq!(
+            tree.par_nearest_neighbors_batch(&query_points, 4),
+            tree.nearest_neighbors_batch(&query_points, 4)
+        );
+    }
+}
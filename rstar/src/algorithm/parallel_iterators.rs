@@ -0,0 +1,290 @@
+use crate::algorithm::intersection_iterator::IntersectionIterator;
+use crate::algorithm::selection_functions::SelectionFunction;
+use crate::node::{ParentNode, RTreeNode};
+use crate::object::RTreeObject;
+use rayon::iter::plumbing::{bridge_unindexed, Folder, UnindexedConsumer, UnindexedProducer};
+use rayon::iter::ParallelIterator;
+
+/// A parallel iterator over all elements selected by a selection function.
+///
+/// Independent subtrees are searched concurrently on rayon's thread pool.
+pub struct ParallelSelectionIterator<'a, T, Func>
+where
+    T: RTreeObject,
+{
+    root: &'a ParentNode<T>,
+    func: Func,
+}
+
+impl<'a, T, Func> ParallelSelectionIterator<'a, T, Func>
+where
+    T: RTreeObject,
+    Func: SelectionFunction<T>,
+{
+    pub fn new(root: &'a ParentNode<T>, func: Func) -> Self {
+        ParallelSelectionIterator { root, func }
+    }
+}
+
+impl<'a, T, Func> ParallelIterator for ParallelSelectionIterator<'a, T, Func>
+where
+    T: RTreeObject + Sync,
+    T::Envelope: Sync,
+    Func: SelectionFunction<T> + Send + Sync,
+{
+    type Item = &'a T;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        let nodes = if self.func.should_unpack_parent(&self.root.envelope) {
+            self.root.children.iter().collect()
+        } else {
+            Vec::new()
+        };
+        let producer = SelectionProducer {
+            func: &self.func,
+            nodes,
+        };
+        bridge_unindexed(producer, consumer)
+    }
+}
+
+/// Searches a list of subtrees that may be split among several threads.
+struct SelectionProducer<'a, 'f, T, Func>
+where
+    T: RTreeObject,
+{
+    func: &'f Func,
+    nodes: Vec<&'a RTreeNode<T>>,
+}
+
+impl<'a, 'f, T, Func> UnindexedProducer for SelectionProducer<'a, 'f, T, Func>
+where
+    T: RTreeObject + Sync,
+    T::Envelope: Sync,
+    Func: SelectionFunction<T> + Sync,
+{
+    type Item = &'a T;
+
+    fn split(mut self) -> (Self, Option<Self>) {
+        // A single parent node is unpacked until its children can be split
+        while let [RTreeNode::Parent(data)] = self.nodes[..] {
+            self.nodes.clear();
+            if self.func.should_unpack_parent(&data.envelope) {
+                self.nodes.extend(&data.children);
+            }
+        }
+        if self.nodes.len() < 2 {
+            return (self, None);
+        }
+        let second_half = self.nodes.split_off(self.nodes.len() / 2);
+        let other = SelectionProducer {
+            func: self.func,
+            nodes: second_half,
+        };
+        (self, Some(other))
+    }
+
+    fn fold_with<F>(self, mut folder: F) -> F
+    where
+        F: Folder<Self::Item>,
+    {
+        let mut nodes = self.nodes;
+        while let Some(next) = nodes.pop() {
+            if folder.full() {
+                break;
+            }
+            match next {
+                RTreeNode::Leaf(ref t) => {
+                    if self.func.should_unpack_leaf(t) {
+                        folder = folder.consume(t);
+                    }
+                }
+                RTreeNode::Parent(ref data) => {
+                    if self.func.should_unpack_parent(&data.envelope) {
+                        nodes.extend(&data.children);
+                    }
+                }
+            }
+        }
+        folder
+    }
+}
+
+/// A parallel iterator over all pairs of elements of two trees whose envelopes intersect.
+///
+/// Independent pairs of nodes are processed concurrently on rayon's thread pool.
+pub struct ParallelIntersectionIterator<'a, T, U = T>
+where
+    T: RTreeObject,
+    U: RTreeObject,
+{
+    iter: IntersectionIterator<'a, T, U>,
+}
+
+impl<'a, T, U> ParallelIntersectionIterator<'a, T, U>
+where
+    T: RTreeObject,
+    U: RTreeObject<Envelope = T::Envelope>,
+{
+    pub fn new(root1: &'a ParentNode<T>, root2: &'a ParentNode<U>) -> Self {
+        ParallelIntersectionIterator {
+            iter: IntersectionIterator::new(root1, root2),
+        }
+    }
+}
+
+impl<'a, T, U> ParallelIterator for ParallelIntersectionIterator<'a, T, U>
+where
+    T: RTreeObject + Sync,
+    T::Envelope: Sync,
+    U: RTreeObject<Envelope = T::Envelope> + Sync,
+{
+    type Item = (&'a T, &'a U);
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge_unindexed(self.iter, consumer)
+    }
+}
+
+impl<'a, T, U> UnindexedProducer for IntersectionIterator<'a, T, U>
+where
+    T: RTreeObject + Sync,
+    T::Envelope: Sync,
+    U: RTreeObject<Envelope = T::Envelope> + Sync,
+{
+    type Item = (&'a T, &'a U);
+
+    fn split(mut self) -> (Self, Option<Self>) {
+        let other = self.split_off();
+        (self, other)
+    }
+
+    fn fold_with<F>(self, folder: F) -> F
+    where
+        F: Folder<Self::Item>,
+    {
+        folder.consume_iter(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::aabb::AABB;
+    use crate::envelope::Envelope;
+    use crate::object::{PointDistance, RTreeObject};
+    use crate::rtree::RTree;
+    use crate::test_utilities::{create_random_points, create_random_rectangles, SEED_1, SEED_2};
+    use crate::SelectionFunction;
+    use rayon::prelude::*;
+
+    #[test]
+    fn test_par_iter() {
+        for &size in &[0, 1, 5, 100, 2000] {
+            let points = create_random_points(size, SEED_1);
+            let tree = RTree::bulk_load(points.clone());
+            let mut found: Vec<_> = tree.par_iter().copied().collect();
+            let mut expected: Vec<_> = tree.iter().copied().collect();
+            found.sort_by(|l, r| l.partial_cmp(r).unwrap());
+            expected.sort_by(|l, r| l.partial_cmp(r).unwrap());
+            assert_eq!(found, expected);
+            assert_eq!(tree.par_iter().count(), size);
+        }
+    }
+
+    #[test]
+    fn test_par_locate_in_envelope() {
+        let rectangles = create_random_rectangles(2000, SEED_1);
+        let tree = RTree::bulk_load(rectangles);
+        for query in create_random_rectangles(50, SEED_2) {
+            let query = query.envelope();
+            let mut found: Vec<_> = tree.par_locate_in_envelope(&query).collect();
+            let mut expected: Vec<_> = tree.locate_in_envelope(&query).collect();
+            found.sort_by(|l, r| l.partial_cmp(r).unwrap());
+            expected.sort_by(|l, r| l.partial_cmp(r).unwrap());
+            assert_eq!(found, expected);
+
+            let found = tree.par_locate_in_envelope_intersecting(&query).count();
+            let expected = tree.locate_in_envelope_intersecting(&query).count();
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn test_par_locate_within_distance() {
+        let points = create_random_points(2000, SEED_1);
+        let tree = RTree::bulk_load(points);
+        for query in create_random_points(50, SEED_2) {
+            let found = tree.par_locate_within_distance(query, 0.01).count();
+            let expected = tree.locate_within_distance(query, 0.01).count();
+            assert_eq!(found, expected);
+            assert!(tree
+                .par_locate_within_distance(query, 0.01)
+                .all(|point| point.distance_2(&query) <= 0.01));
+        }
+    }
+
+    #[test]
+    fn test_par_intersection_candidates() {
+        let rectangles1 = create_random_rectangles(2000, SEED_1);
+        let rectangles2 = create_random_rectangles(500, SEED_2);
+        let tree1 = RTree::bulk_load(rectangles1);
+        let tree2 = RTree::bulk_load(rectangles2);
+        let mut found: Vec<_> = tree1
+            .par_intersection_candidates_with_other_tree(&tree2)
+            .collect();
+        let mut expected: Vec<_> = tree1
+            .intersection_candidates_with_other_tree(&tree2)
+            .collect();
+        assert!(!expected.is_empty());
+        found.sort_by(|l, r| l.partial_cmp(r).unwrap());
+        expected.sort_by(|l, r| l.partial_cmp(r).unwrap());
+        assert_eq!(found, expected);
+
+        let points = RTree::bulk_load(create_random_points(1000, SEED_1));
+        assert_eq!(
+            points
+                .par_intersection_candidates_with_other_tree(&points)
+                .count(),
+            points.size()
+        );
+        let empty: RTree<[f64; 2]> = RTree::new();
+        assert_eq!(
+            points
+                .par_intersection_candidates_with_other_tree(&empty)
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_par_locate_with_selection_function() {
+        struct SelectNoneFunc;
+
+        impl SelectionFunction<[f64; 2]> for SelectNoneFunc {
+            fn should_unpack_parent(&self, _: &AABB<[f64; 2]>) -> bool {
+                false
+            }
+        }
+
+        let tree = RTree::bulk_load(create_random_points(1000, SEED_1));
+        assert_eq!(
+            tree.par_locate_with_selection_function(SelectNoneFunc)
+                .count(),
+            0
+        );
+        let envelope = AABB::from_corners([0.0, 0.0], [0.5, 0.5]);
+        let found = tree
+            .par_locate_with_selection_function(
+                crate::algorithm::selection_functions::SelectInEnvelopeFunction::new(envelope),
+            )
+            .count();
+        assert_eq!(found, tree.locate_in_envelope(&envelope).count());
+        assert!(tree.par_iter().any(|point| envelope.contains_point(point)));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::algorithm::selection_functions::SelectionFunction;
+use crate::envelope::Envelope;
 use crate::node::{ParentNode, RTreeNode};
 use crate::object::RTreeObject;
 use crate::params::RTreeParams;
@@ -44,7 +45,8 @@ where
         }
         // Perform the actual removal outside of the self.children borrow
         if let Some(removal_index) = removal_index {
-            let child = node.children.swap_remove(removal_index);
+            // Keep the order of the remaining children, insertion strategies may rely on it
+            let child = node.children.remove(removal_index);
             if result.is_none() {
                 if let RTreeNode::Leaf(t) = child {
                     result = Some(t);
@@ -61,6 +63,65 @@ where
     result
 }
 
+/// Removes all elements selected by a selection function from a tree.
+///
+/// Subtrees whose envelope is fully contained within `envelope` are detached as a whole and
+/// added to `detached_subtrees`. This requires that the selection function selects all elements
+/// contained within `envelope`. Other selected elements are added to `detached_leaves`.
+///
+/// Nodes that become empty are removed from their parents.
+pub fn split_off<T, R>(
+    node: &mut ParentNode<T>,
+    envelope: &T::Envelope,
+    selection_function: &R,
+    detached_subtrees: &mut Vec<ParentNode<T>>,
+    detached_leaves: &mut Vec<T>,
+) where
+    T: RTreeObject,
+    R: SelectionFunction<T>,
+{
+    if !selection_function.should_unpack_parent(&node.envelope) {
+        return;
+    }
+    let mut changed = false;
+    // Iterate backwards, swap_remove will only move already visited children
+    for index in (0..node.children.len()).rev() {
+        let should_remove = match node.children[index] {
+            RTreeNode::Parent(ref mut data) => {
+                if envelope.contains_envelope(&data.envelope) {
+                    true
+                } else {
+                    let previous_len = detached_subtrees.len() + detached_leaves.len();
+                    split_off(
+                        data,
+                        envelope,
+                        selection_function,
+                        detached_subtrees,
+                        detached_leaves,
+                    );
+                    changed |= previous_len != detached_subtrees.len() + detached_leaves.len();
+                    data.children.is_empty()
+                }
+            }
+            RTreeNode::Leaf(ref t) => selection_function.should_unpack_leaf(t),
+        };
+        if should_remove {
+            changed = true;
+            match node.children.swap_remove(index) {
+                RTreeNode::Parent(data) => {
+                    if !data.children.is_empty() {
+                        detached_subtrees.push(data);
+                    }
+                }
+                RTreeNode::Leaf(t) => detached_leaves.push(t),
+            }
+        }
+    }
+    if changed {
+        node.envelope = crate::node::envelope_for_children(&node.children);
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::point::PointExt;
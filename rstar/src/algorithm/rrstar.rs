@@ -0,0 +1,300 @@
+use crate::algorithm::guttman::insert_with_split;
+use crate::algorithm::rstar::get_split_axis;
+use crate::envelope::Envelope;
+use crate::node::{envelope_for_children, ParentNode, RTreeNode};
+use crate::object::RTreeObject;
+use crate::params::{InsertionStrategy, RTreeConfig, RTreeParams};
+use crate::point::{scalar_from_usize, Point, RTreeNum};
+use crate::rtree::RTree;
+use num_traits::Zero;
+
+/// Inserts elements according to the revised r*-tree (RR*-tree) heuristic.
+///
+/// The RR*-tree was proposed by Beckmann and Seeger as an improvement of the r*-tree. It
+/// does not perform any forced reinsertion. Instead, subtrees are chosen by their perimeter and
+/// overlap enlargement, considering only a small set of candidates, and splits are weighted
+/// towards balanced distributions with small overlap and perimeter. This usually yields faster
+/// insertions than [RStarInsertionStrategy](crate::RStarInsertionStrategy) with a comparable
+/// or better tree quality.
+///
+/// `RTreeParams::REINSERTION_COUNT` is ignored by this strategy.
+///
+/// # References
+/// [Beckmann, Norbert, and Bernhard Seeger. "A revised r*-tree in comparison with related index structures." Proceedings of the 2009 ACM SIGMOD International Conference on Management of data. 2009.](https://doi.org/10.1145/1559845.1559929)
+///
+/// # Example
+/// ```
+/// use rstar::{RTree, RTreeParams, RRStarInsertionStrategy};
+///
+/// struct RRStarParams;
+///
+/// impl RTreeParams for RRStarParams {
+///     const MIN_SIZE: usize = 5;
+///     const MAX_SIZE: usize = 16;
+///     const REINSERTION_COUNT: usize = 0;
+///     type DefaultInsertionStrategy = RRStarInsertionStrategy;
+/// }
+///
+/// let mut tree: RTree<_, RRStarParams> = RTree::new_with_params();
+/// tree.insert([0.0, 1.0]);
+/// assert_eq!(tree.size(), 1);
+/// ```
+pub enum RRStarInsertionStrategy {}
+
+impl InsertionStrategy for RRStarInsertionStrategy {
+    fn insert<T, Params>(tree: &mut RTree<T, Params>, t: T)
+    where
+        Params: RTreeParams,
+        T: RTreeObject,
+    {
+        let config = *tree.config();
+        insert_with_split(tree.root_mut(), t, &config, choose_subtree, split);
+    }
+}
+
+type Scalar<T> = <<<T as RTreeObject>::Envelope as Envelope>::Point as Point>::Scalar;
+
+fn choose_subtree<T>(node: &ParentNode<T>, envelope: &T::Envelope) -> Option<usize>
+where
+    T: RTreeObject,
+{
+    match node.children.first() {
+        Some(RTreeNode::Parent(_)) => {}
+        _ => return None,
+    }
+    let envelopes: Vec<_> = node.children.iter().map(RTreeObject::envelope).collect();
+
+    // Prefer children that already cover the new envelope
+    let covering = envelopes
+        .iter()
+        .enumerate()
+        .filter(|(_, child)| child.contains_envelope(envelope))
+        .map(|(index, child)| ((child.area(), child.perimeter_value()), index))
+        .min_by(|l, r| l.0.partial_cmp(&r.0).unwrap());
+    if let Some((_, index)) = covering {
+        return Some(index);
+    }
+
+    let mut order: Vec<_> = envelopes
+        .iter()
+        .map(|child| child.merged(envelope).perimeter_value() - child.perimeter_value())
+        .enumerate()
+        .collect();
+    order.sort_by(|l, r| l.1.partial_cmp(&r.1).unwrap());
+
+    // Only consider children up to the last one whose overlap with the first candidate
+    // would increase
+    let first = order[0].0;
+    let enlarged_first = envelopes[first].merged(envelope);
+    let last_candidate = order
+        .iter()
+        .rposition(|&(index, _)| {
+            enlarged_first.intersection_area(&envelopes[index])
+                > envelopes[first].intersection_area(&envelopes[index])
+        })
+        .unwrap_or(0);
+    if last_candidate == 0 {
+        return Some(first);
+    }
+
+    let mut best = (Scalar::<T>::zero(), first);
+    for (position, &(candidate, _)) in order[..=last_candidate].iter().enumerate() {
+        let overlap_enlargement = overlap_enlargement::<T>(&envelopes, candidate, envelope);
+        if overlap_enlargement == Zero::zero() {
+            return Some(candidate);
+        }
+        if position == 0 || overlap_enlargement < best.0 {
+            best = (overlap_enlargement, candidate);
+        }
+    }
+    Some(best.1)
+}
+
+fn overlap_enlargement<T>(
+    envelopes: &[T::Envelope],
+    candidate: usize,
+    envelope: &T::Envelope,
+) -> Scalar<T>
+where
+    T: RTreeObject,
+{
+    let original = envelopes[candidate];
+    let enlarged = original.merged(envelope);
+    envelopes
+        .iter()
+        .enumerate()
+        .filter(|&(index, _)| index != candidate)
+        .fold(Zero::zero(), |sum, (_, other)| {
+            sum + enlarged.intersection_area(other) - original.intersection_area(other)
+        })
+}
+
+/// Goodness of a split candidate, `numerator / denominator` is to be minimized.
+struct SplitGoodness<S> {
+    numerator: S,
+    denominator: S,
+}
+
+impl<S: Copy + ::std::ops::Mul<Output = S> + PartialOrd> SplitGoodness<S> {
+    fn is_better_than(&self, other: &Self) -> bool {
+        self.numerator * other.denominator < other.numerator * self.denominator
+    }
+}
+
+fn split<T>(node: &mut ParentNode<T>, config: &RTreeConfig) -> Option<RTreeNode<T>>
+where
+    T: RTreeObject,
+{
+    let axis = get_split_axis(node, config);
+    T::Envelope::sort_envelopes(axis, &mut node.children);
+
+    let len = node.children.len();
+    let min_size = config.min_size;
+    let mut prefix_envelopes = Vec::with_capacity(len);
+    let mut suffix_envelopes = vec![T::Envelope::new_empty(); len + 1];
+    let mut current = T::Envelope::new_empty();
+    for child in &node.children {
+        current.merge(&child.envelope());
+        prefix_envelopes.push(current);
+    }
+    for (index, child) in node.children.iter().enumerate().rev() {
+        suffix_envelopes[index] = suffix_envelopes[index + 1].merged(&child.envelope());
+    }
+
+    let candidates = min_size..=len - min_size;
+    let overlap_free = candidates
+        .clone()
+        .any(|k| prefix_envelopes[k - 1].intersection_area(&suffix_envelopes[k]) == Zero::zero());
+    let max_perimeter = node.envelope.perimeter_value() + node.envelope.perimeter_value();
+
+    let mut best_index = min_size;
+    let mut best = None;
+    for k in candidates {
+        let (first, second) = (prefix_envelopes[k - 1], suffix_envelopes[k]);
+        let overlap = first.intersection_area(&second);
+        // Prefer overlap free distributions with small perimeter, otherwise minimize the overlap.
+        let raw_goodness = if !overlap_free {
+            overlap
+        } else if overlap == Zero::zero() {
+            first.perimeter_value() + second.perimeter_value() - max_perimeter
+        } else {
+            continue;
+        };
+        let weight = weight::<Scalar<T>>(k, config.max_size);
+        // Both cases favor distributions with a high weight
+        let goodness = if raw_goodness < Zero::zero() {
+            SplitGoodness {
+                numerator: raw_goodness * weight,
+                denominator: percent(),
+            }
+        } else {
+            SplitGoodness {
+                numerator: raw_goodness * percent(),
+                denominator: weight,
+            }
+        };
+        if best
+            .as_ref()
+            .map(|best| goodness.is_better_than(best))
+            .unwrap_or(true)
+        {
+            best = Some(goodness);
+            best_index = k;
+        }
+    }
+    let off_split = node.children.split_off(best_index);
+    node.envelope = envelope_for_children(&node.children);
+    Some(RTreeNode::Parent(ParentNode::new_parent(off_split)))
+}
+
+/// Returns `100` as an arbitrary scalar type.
+fn percent<S: RTreeNum>() -> S {
+    scalar_from_usize(100)
+}
+
+/// Weights the distribution of `index` elements into the first node (in percent).
+///
+/// Balanced distributions are favored by a gaussian weighting function. Its parameters are
+/// chosen as suggested for the RR*-tree, assuming that the node's center has not moved since its
+/// creation.
+fn weight<S: RTreeNum>(index: usize, max_size: usize) -> S {
+    const SIGMA: f64 = 0.5;
+    let x = 2. * index as f64 / (max_size + 1) as f64 - 1.;
+    let y1 = (-1. / (SIGMA * SIGMA)).exp();
+    let ys = 1. / (1. - y1);
+    let weight = ys * ((-(x / SIGMA) * (x / SIGMA)).exp() - y1);
+    let percent = (weight * 100.).round().clamp(1., 100.) as usize;
+    scalar_from_usize(percent)
+}
+
+#[cfg(test)]
+mod test {
+    use super::RRStarInsertionStrategy;
+    use crate::node::{ParentNode, RTreeNode};
+    use crate::params::{RTreeConfig, RTreeParams};
+    use crate::test_utilities::*;
+    use crate::{RTree, RTreeObject};
+
+    struct RRStarParams;
+
+    impl RTreeParams for RRStarParams {
+        const MIN_SIZE: usize = 3;
+        const MAX_SIZE: usize = 8;
+        const REINSERTION_COUNT: usize = 0;
+        type DefaultInsertionStrategy = RRStarInsertionStrategy;
+    }
+
+    #[test]
+    fn test_weight_favors_balanced_splits() {
+        let weights: Vec<i32> = (1..=8).map(|k| super::weight(k, 8)).collect();
+        assert!(weights.iter().all(|w| (1..=100).contains(w)));
+        assert!(weights[3] > weights[0]);
+        assert!(weights[4] > weights[7]);
+    }
+
+    #[test]
+    fn test_overlap_free_split_is_balanced() {
+        let config = RTreeConfig::from_params::<RRStarParams>();
+        let children = (0..=config.max_size)
+            .map(|x| RTreeNode::Leaf([x as f64, 0.0]))
+            .collect();
+        let mut node = ParentNode::new_parent(children);
+        let other = super::split(&mut node, &config);
+        let len = node.children.len();
+        assert!(
+            len == 4 || len == 5,
+            "Unbalanced split into {} children",
+            len
+        );
+        match other {
+            Some(RTreeNode::Parent(other)) => assert_eq!(other.children.len() + len, 9),
+            _ => panic!("Expected a new parent node"),
+        }
+    }
+
+    #[test]
+    fn test_rrstar_insertion() {
+        check_insertion(create_random_points(1000, SEED_1));
+        check_insertion(create_random_rectangles(1000, SEED_1));
+        check_insertion(vec![[1, 1]; 100]);
+    }
+
+    fn check_insertion<T>(elements: Vec<T>)
+    where
+        T: RTreeObject + PartialEq + Clone,
+    {
+        let mut tree: RTree<T, RRStarParams> = RTree::new_with_params();
+        for element in &elements {
+            tree.insert(element.clone());
+            tree.root().sanity_check::<RRStarParams>(true);
+        }
+        assert_eq!(tree.size(), elements.len());
+        for element in &elements {
+            assert!(tree.contains(element));
+        }
+        for element in &elements {
+            assert!(tree.remove(element).is_some());
+        }
+        assert_eq!(tree.size(), 0);
+    }
+}
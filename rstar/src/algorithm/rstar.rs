@@ -1,7 +1,7 @@
 use crate::envelope::Envelope;
 use crate::node::{envelope_for_children, ParentNode, RTreeNode};
 use crate::object::RTreeObject;
-use crate::params::{InsertionStrategy, RTreeParams};
+use crate::params::{InsertionStrategy, RTreeConfig, RTreeParams};
 use crate::point::{Point, PointExt};
 use crate::rtree::RTree;
 use num_traits::{Bounded, Zero};
@@ -38,7 +38,8 @@ impl InsertionStrategy for RStarInsertionStrategy {
             PerformReinsert(RTreeNode<T>),
         }
 
-        let first = recursive_insert::<_, Params>(tree.root_mut(), RTreeNode::Leaf(t), 0);
+        let config = *tree.config();
+        let first = recursive_insert(tree.root_mut(), RTreeNode::Leaf(t), 0, &config);
         let mut target_height = 0;
         let mut insertion_stack = Vec::new();
         match first {
@@ -54,7 +55,7 @@ impl InsertionStrategy for RStarInsertionStrategy {
             match next {
                 PerformSplit(node) => {
                     // The root node was split, create a new root and increase height
-                    let new_root = ParentNode::new_root::<Params>();
+                    let new_root = ParentNode::new_root(&config);
                     let old_root = ::std::mem::replace(tree.root_mut(), new_root);
                     let new_envelope = old_root.envelope.merged(&node.envelope());
                     let root = tree.root_mut();
@@ -65,7 +66,7 @@ impl InsertionStrategy for RStarInsertionStrategy {
                 }
                 PerformReinsert(node_to_reinsert) => {
                     let root = tree.root_mut();
-                    match forced_insertion::<T, Params>(root, node_to_reinsert, target_height) {
+                    match forced_insertion(root, node_to_reinsert, target_height, &config) {
                         InsertionResult::Split(node) => insertion_stack.push(PerformSplit(node)),
                         InsertionResult::Reinsert(_, _) => {
                             panic!("Unexpected reinsert. This is a bug in rstar.")
@@ -78,30 +79,59 @@ impl InsertionStrategy for RStarInsertionStrategy {
     }
 }
 
-fn forced_insertion<T, Params>(
+/// Inserts a node into the tree's root without performing any reinsertion.
+///
+/// `depth` specifies the depth of the parent that receives the node, a depth of 0 refers to
+/// the root itself. Overflowing nodes are split. Returns `true` if the root was split and
+/// the tree's height has thus increased by one.
+pub(crate) fn insert_at_depth<T>(
+    root: &mut ParentNode<T>,
+    node: RTreeNode<T>,
+    depth: usize,
+    config: &RTreeConfig,
+) -> bool
+where
+    T: RTreeObject,
+{
+    match forced_insertion(root, node, depth, config) {
+        InsertionResult::Split(node) => {
+            let old_root = ::std::mem::replace(root, ParentNode::new_root(config));
+            root.envelope = old_root.envelope.merged(&node.envelope());
+            root.children.push(RTreeNode::Parent(old_root));
+            root.children.push(node);
+            true
+        }
+        InsertionResult::Reinsert(_, _) => {
+            panic!("Unexpected reinsert. This is a bug in rstar.")
+        }
+        InsertionResult::Complete => false,
+    }
+}
+
+fn forced_insertion<T>(
     node: &mut ParentNode<T>,
     t: RTreeNode<T>,
     target_height: usize,
+    config: &RTreeConfig,
 ) -> InsertionResult<T>
 where
     T: RTreeObject,
-    Params: RTreeParams,
 {
     node.envelope.merge(&t.envelope());
-    let expand_index = choose_subtree(node, &t);
+    let expand_index = choose_subtree(node, &t.envelope());
 
     if target_height == 0 || node.children.len() < expand_index {
         // Force insertion into this node
         node.children.push(t);
-        return resolve_overflow_without_reinsertion::<_, Params>(node);
+        return resolve_overflow_without_reinsertion(node, config);
     }
 
     if let RTreeNode::Parent(ref mut follow) = node.children[expand_index] {
-        match forced_insertion::<_, Params>(follow, t, target_height - 1) {
+        match forced_insertion(follow, t, target_height - 1, config) {
             InsertionResult::Split(child) => {
                 node.envelope.merge(&child.envelope());
                 node.children.push(child);
-                resolve_overflow_without_reinsertion::<_, Params>(node)
+                resolve_overflow_without_reinsertion(node, config)
             }
             other => other,
         }
@@ -110,26 +140,26 @@ where
     }
 }
 
-fn recursive_insert<T, Params>(
+fn recursive_insert<T>(
     node: &mut ParentNode<T>,
     t: RTreeNode<T>,
     current_height: usize,
+    config: &RTreeConfig,
 ) -> InsertionResult<T>
 where
     T: RTreeObject,
-    Params: RTreeParams,
 {
     node.envelope.merge(&t.envelope());
-    let expand_index = choose_subtree(node, &t);
+    let expand_index = choose_subtree(node, &t.envelope());
 
     if node.children.len() < expand_index {
         // Force insertion into this node
         node.children.push(t);
-        return resolve_overflow::<_, Params>(node, current_height);
+        return resolve_overflow(node, current_height, config);
     }
 
     let expand = if let RTreeNode::Parent(ref mut follow) = node.children[expand_index] {
-        recursive_insert::<_, Params>(follow, t, current_height + 1)
+        recursive_insert(follow, t, current_height + 1, config)
     } else {
         panic!("This is a bug in rstar.")
     };
@@ -138,7 +168,7 @@ where
         InsertionResult::Split(child) => {
             node.envelope.merge(&child.envelope());
             node.children.push(child);
-            resolve_overflow::<_, Params>(node, current_height)
+            resolve_overflow(node, current_height, config)
         }
         InsertionResult::Reinsert(a, b) => {
             node.envelope = envelope_for_children(&node.children);
@@ -148,7 +178,7 @@ where
     }
 }
 
-fn choose_subtree<T>(node: &mut ParentNode<T>, to_insert: &RTreeNode<T>) -> usize
+pub(crate) fn choose_subtree<T>(node: &ParentNode<T>, to_insert: &T::Envelope) -> usize
 where
     T: RTreeObject,
 {
@@ -163,7 +193,7 @@ where
     };
 
     let zero: <<T::Envelope as Envelope>::Point as Point>::Scalar = Zero::zero();
-    let insertion_envelope = to_insert.envelope();
+    let insertion_envelope = *to_insert;
     let mut inclusion_count = 0;
     let mut min_area = <<T::Envelope as Envelope>::Point as Point>::Scalar::max_value();
     let mut min_index = 0;
@@ -218,46 +248,61 @@ where
 }
 
 // Never returns a request for reinsertion
-fn resolve_overflow_without_reinsertion<T, Params>(node: &mut ParentNode<T>) -> InsertionResult<T>
+fn resolve_overflow_without_reinsertion<T>(
+    node: &mut ParentNode<T>,
+    config: &RTreeConfig,
+) -> InsertionResult<T>
 where
     T: RTreeObject,
-    Params: RTreeParams,
 {
-    if node.children.len() > Params::MAX_SIZE {
-        let off_split = split::<_, Params>(node);
+    if node.children.len() > config.max_size {
+        let off_split = split(node, config);
         InsertionResult::Split(off_split)
     } else {
         InsertionResult::Complete
     }
 }
 
-fn resolve_overflow<T, Params>(node: &mut ParentNode<T>, current_depth: usize) -> InsertionResult<T>
+fn resolve_overflow<T>(
+    node: &mut ParentNode<T>,
+    current_depth: usize,
+    config: &RTreeConfig,
+) -> InsertionResult<T>
 where
     T: RTreeObject,
-    Params: RTreeParams,
 {
-    if Params::REINSERTION_COUNT == 0 {
-        resolve_overflow_without_reinsertion::<_, Params>(node)
-    } else if node.children.len() > Params::MAX_SIZE {
-        let nodes_for_reinsertion = get_nodes_for_reinsertion::<_, Params>(node);
+    if config.reinsertion_count == 0 {
+        resolve_overflow_without_reinsertion(node, config)
+    } else if node.children.len() > config.max_size {
+        let nodes_for_reinsertion = get_nodes_for_reinsertion(node, config);
         InsertionResult::Reinsert(nodes_for_reinsertion, current_depth)
     } else {
         InsertionResult::Complete
     }
 }
 
-fn split<T, Params>(node: &mut ParentNode<T>) -> RTreeNode<T>
+pub(crate) fn split<T>(node: &mut ParentNode<T>, config: &RTreeConfig) -> RTreeNode<T>
+where
+    T: RTreeObject,
+{
+    let axis = get_split_axis(node, config);
+    split_along_axis(node, axis, config)
+}
+
+pub(crate) fn split_along_axis<T>(
+    node: &mut ParentNode<T>,
+    axis: usize,
+    config: &RTreeConfig,
+) -> RTreeNode<T>
 where
     T: RTreeObject,
-    Params: RTreeParams,
 {
-    let axis = get_split_axis::<_, Params>(node);
     let zero = <<T::Envelope as Envelope>::Point as Point>::Scalar::zero();
     debug_assert!(node.children.len() >= 2);
     // Sort along axis
     T::Envelope::sort_envelopes(axis, &mut node.children);
     let mut best = (zero, zero);
-    let min_size = Params::MIN_SIZE;
+    let min_size = config.min_size;
     let mut best_index = min_size;
 
     for k in min_size..=node.children.len() - min_size {
@@ -284,14 +329,13 @@ where
     RTreeNode::Parent(ParentNode::new_parent(off_split))
 }
 
-fn get_split_axis<T, Params>(node: &mut ParentNode<T>) -> usize
+pub(crate) fn get_split_axis<T>(node: &mut ParentNode<T>, config: &RTreeConfig) -> usize
 where
     T: RTreeObject,
-    Params: RTreeParams,
 {
     let mut best_goodness = <<T::Envelope as Envelope>::Point as Point>::Scalar::max_value();
     let mut best_axis = 0;
-    let min_size = Params::MIN_SIZE;
+    let min_size = config.min_size;
     let until = node.children.len() - min_size + 1;
     for axis in 0..<T::Envelope as Envelope>::Point::DIMENSIONS {
         // Sort children along the current axis
@@ -326,10 +370,9 @@ where
     best_axis
 }
 
-fn get_nodes_for_reinsertion<T, Params>(node: &mut ParentNode<T>) -> Vec<RTreeNode<T>>
+fn get_nodes_for_reinsertion<T>(node: &mut ParentNode<T>, config: &RTreeConfig) -> Vec<RTreeNode<T>>
 where
     T: RTreeObject,
-    Params: RTreeParams,
 {
     let center = node.envelope.center();
     // Sort with increasing order so we can use Vec::split_off
@@ -345,7 +388,7 @@ where
     let num_children = node.children.len();
     let result = node
         .children
-        .split_off(num_children - Params::REINSERTION_COUNT);
+        .split_off(num_children - config.reinsertion_count);
     node.envelope = envelope_for_children(&node.children);
     result
 }
@@ -0,0 +1,335 @@
+use crate::algorithm::guttman::insert_with_split;
+use crate::algorithm::rstar;
+use crate::envelope::Envelope;
+use crate::node::{envelope_for_children, ParentNode, RTreeNode};
+use crate::object::RTreeObject;
+use crate::params::{InsertionStrategy, RTreeConfig, RTreeParams};
+use crate::point::{scalar_from_usize, Point};
+use crate::rtree::RTree;
+use num_traits::Zero;
+
+/// Inserts elements according to the X-tree heuristic, avoiding highly overlapping nodes by
+/// creating supernodes.
+///
+/// In higher dimensions (roughly from 5 dimensions upwards), r*-tree splits of inner nodes
+/// often produce nodes that overlap heavily, causing queries to visit large parts of the tree.
+/// The X-tree first attempts a regular r* split. If the resulting nodes overlap by more than 20%,
+/// it attempts an overlap free split instead. If no overlap free split exists that puts at least
+/// `RTreeParams::MIN_SIZE` children into both nodes, the node is not split at all but is
+/// extended beyond `RTreeParams::MAX_SIZE`, becoming a _supernode_. Supernodes are searched
+/// linearly, which is faster than visiting several overlapping nodes. Leaf level nodes are
+/// always split.
+///
+/// Each node records the axes along which it has been split (its _split history_). Overlap free
+/// splits are only searched along the axes that all children of a node have been split along.
+/// For nodes that have not been created by this strategy, e.g. after bulk loading or
+/// deserialization, all axes are searched instead.
+///
+/// Use this strategy for high dimensional data only, for low dimensional data it behaves
+/// similar to [RStarInsertionStrategy](crate::RStarInsertionStrategy) without forced
+/// reinsertion. `RTreeParams::REINSERTION_COUNT` is ignored by this strategy.
+///
+/// # References
+/// [Berchtold, Stefan, Daniel A. Keim, and Hans-Peter Kriegel. "The X-tree: An index structure for high-dimensional data." Proceedings of the 22nd International Conference on Very Large Data Bases. 1996.](http://www.vldb.org/conf/1996/P028.PDF)
+///
+/// # Example
+/// ```
+/// use rstar::{RTree, RTreeParams, XTreeInsertionStrategy};
+///
+/// struct XTreeParams;
+///
+/// impl RTreeParams for XTreeParams {
+///     const MIN_SIZE: usize = 8;
+///     const MAX_SIZE: usize = 24;
+///     const REINSERTION_COUNT: usize = 0;
+///     type DefaultInsertionStrategy = XTreeInsertionStrategy;
+/// }
+///
+/// let mut tree: RTree<_, XTreeParams> = RTree::new_with_params();
+/// tree.insert([0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]);
+/// assert_eq!(tree.size(), 1);
+/// ```
+pub enum XTreeInsertionStrategy {}
+
+impl InsertionStrategy for XTreeInsertionStrategy {
+    fn insert<T, Params>(tree: &mut RTree<T, Params>, t: T)
+    where
+        Params: RTreeParams,
+        T: RTreeObject,
+    {
+        let config = *tree.config();
+        insert_with_split(tree.root_mut(), t, &config, choose_subtree, split);
+    }
+}
+
+type Scalar<T> = <<<T as RTreeObject>::Envelope as Envelope>::Point as Point>::Scalar;
+
+fn choose_subtree<T>(node: &ParentNode<T>, envelope: &T::Envelope) -> Option<usize>
+where
+    T: RTreeObject,
+{
+    match rstar::choose_subtree(node, envelope) {
+        index if index < node.children.len() => Some(index),
+        _ => None,
+    }
+}
+
+fn split<T>(node: &mut ParentNode<T>, config: &RTreeConfig) -> Option<RTreeNode<T>>
+where
+    T: RTreeObject,
+{
+    let is_leaf_level = node
+        .children
+        .first()
+        .map(RTreeNode::is_leaf)
+        .unwrap_or(true);
+    if is_leaf_level {
+        let axis = rstar::get_split_axis(node, config);
+        let off_split = rstar::split_along_axis(node, axis, config);
+        return Some(record_split(node, off_split, axis));
+    }
+    // Supernodes are only split if an overlap free split is found
+    if node.children.len() == config.max_size + 1 {
+        let axis = rstar::get_split_axis(node, config);
+        let off_split = rstar::split_along_axis(node, axis, config);
+        if !is_overlap_too_high(&node.envelope, &off_split.envelope()) {
+            return Some(record_split(node, off_split, axis));
+        }
+        if let RTreeNode::Parent(data) = off_split {
+            node.children.extend(data.children);
+        }
+        node.envelope = envelope_for_children(&node.children);
+    }
+    overlap_free_split(node, config)
+}
+
+/// Adds `axis` to the split history of both nodes resulting from a split.
+fn record_split<T>(node: &mut ParentNode<T>, off_split: RTreeNode<T>, axis: usize) -> RTreeNode<T>
+where
+    T: RTreeObject,
+{
+    node.split_history |= axis_bit(axis);
+    match off_split {
+        RTreeNode::Parent(mut data) => {
+            data.split_history = node.split_history;
+            RTreeNode::Parent(data)
+        }
+        RTreeNode::Leaf(_) => unreachable!("This is a bug."),
+    }
+}
+
+/// Returns the bit representing `axis` in a split history. Axes beyond the 64th are not
+/// recorded.
+fn axis_bit(axis: usize) -> u64 {
+    1u64.checked_shl(axis as u32).unwrap_or(0)
+}
+
+/// Returns the axes along which an overlap free split may exist, according to the split
+/// history of the node's children.
+fn split_axes<T>(node: &ParentNode<T>) -> Vec<usize>
+where
+    T: RTreeObject,
+{
+    let dimensions = <T::Envelope as Envelope>::Point::DIMENSIONS;
+    let mut common_history = !0;
+    for child in &node.children {
+        match child {
+            RTreeNode::Parent(ref data) if data.split_history != 0 => {
+                common_history &= data.split_history;
+            }
+            // The history is unknown, fall back to searching all axes
+            _ => return (0..dimensions).collect(),
+        }
+    }
+    (0..dimensions)
+        .filter(|axis| common_history & axis_bit(*axis) != 0)
+        .collect()
+}
+
+/// Returns `true` if two nodes overlap by more than 20% of their combined area.
+fn is_overlap_too_high<E: Envelope>(first: &E, second: &E) -> bool {
+    let five: <E::Point as Point>::Scalar = scalar_from_usize(5);
+    let overlap = first.intersection_area(second);
+    let union = first.area() + second.area() - overlap;
+    overlap * five > union
+}
+
+/// Splits a node into two nodes that do not overlap.
+///
+/// The axes given by the split history are checked for a split without overlap that puts at
+/// least `MIN_SIZE` children into both nodes. The most balanced of these splits is chosen.
+/// Returns `None` if no such split exists.
+fn overlap_free_split<T>(node: &mut ParentNode<T>, config: &RTreeConfig) -> Option<RTreeNode<T>>
+where
+    T: RTreeObject,
+{
+    let len = node.children.len();
+    let min_size = config.min_size.max(1);
+    if len < 2 * min_size {
+        return None;
+    }
+    // Each candidate is ((imbalance, perimeter), axis, split index)
+    let mut best: Option<((usize, Scalar<T>), usize, usize)> = None;
+    for axis in split_axes(node) {
+        T::Envelope::sort_envelopes(axis, &mut node.children);
+        let mut suffix_envelopes = vec![T::Envelope::new_empty(); len + 1];
+        for (index, child) in node.children.iter().enumerate().rev() {
+            suffix_envelopes[index] = suffix_envelopes[index + 1].merged(&child.envelope());
+        }
+        let mut prefix_envelope = T::Envelope::new_empty();
+        for (index, child) in node.children[..len - min_size].iter().enumerate() {
+            prefix_envelope.merge(&child.envelope());
+            let k = index + 1;
+            if k < min_size {
+                continue;
+            }
+            let second_envelope = suffix_envelopes[k];
+            if prefix_envelope.intersection_area(&second_envelope) != Zero::zero() {
+                continue;
+            }
+            let imbalance = (2 * k).max(len) - (2 * k).min(len);
+            let perimeter = prefix_envelope.perimeter_value() + second_envelope.perimeter_value();
+            let goodness = (imbalance, perimeter);
+            if best
+                .as_ref()
+                .map(|(best, _, _)| goodness < *best)
+                .unwrap_or(true)
+            {
+                best = Some((goodness, axis, k));
+            }
+        }
+    }
+    let (_, axis, k) = best?;
+    T::Envelope::sort_envelopes(axis, &mut node.children);
+    let off_split = node.children.split_off(k);
+    node.envelope = envelope_for_children(&node.children);
+    let off_split = RTreeNode::Parent(ParentNode::new_parent(off_split));
+    Some(record_split(node, off_split, axis))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{axis_bit, overlap_free_split, XTreeInsertionStrategy};
+    use crate::algorithm::iterators::RTreeIterator;
+    use crate::algorithm::selection_functions::SelectAllFunc;
+    use crate::node::{ParentNode, RTreeNode};
+    use crate::params::{RTreeConfig, RTreeParams};
+    use crate::test_utilities::*;
+    use crate::{RTree, RTreeObject};
+    use rand::{Rng, SeedableRng};
+    use rand_hc::Hc128Rng;
+
+    #[derive(Clone)]
+    struct XTreeParams;
+
+    impl RTreeParams for XTreeParams {
+        const MIN_SIZE: usize = 3;
+        const MAX_SIZE: usize = 8;
+        const REINSERTION_COUNT: usize = 0;
+        type DefaultInsertionStrategy = XTreeInsertionStrategy;
+    }
+
+    #[test]
+    fn test_xtree_insertion() {
+        check_insertion(create_random_points(1000, SEED_1));
+        check_insertion(create_random_rectangles(1000, SEED_1));
+        check_insertion(vec![[1, 1]; 100]);
+    }
+
+    #[test]
+    fn test_xtree_creates_supernodes_for_high_dimensional_data() {
+        let mut rng = Hc128Rng::from_seed(*SEED_1);
+        let points: Vec<[f64; 9]> = (0..2000).map(|_| rng.gen()).collect();
+        let tree = check_insertion(points);
+        assert!(max_inner_node_size(tree.root()) > XTreeParams::MAX_SIZE);
+    }
+
+    #[test]
+    fn test_xtree_records_split_history() {
+        let tree = check_insertion(create_random_points(1000, SEED_1));
+        assert!(all_nodes_have_split_history(tree.root()));
+    }
+
+    #[test]
+    fn test_overlap_free_split_uses_split_history() {
+        let config = RTreeConfig::from_params::<XTreeParams>();
+        // The children can only be separated along the first axis
+        let create_node = |histories: &[u64]| {
+            let children = histories
+                .iter()
+                .enumerate()
+                .map(|(index, history)| {
+                    let (x, y) = (index as f64 * 10.0, (index % 2) as f64);
+                    let mut child = ParentNode::new_parent(vec![
+                        RTreeNode::Leaf([x, y]),
+                        RTreeNode::Leaf([x + 1.0, 100.0]),
+                    ]);
+                    child.split_history = *history;
+                    RTreeNode::Parent(child)
+                })
+                .collect();
+            ParentNode::new_parent(children)
+        };
+        let mut node = create_node(&[axis_bit(1); 6]);
+        assert!(overlap_free_split(&mut node, &config).is_none());
+        assert_eq!(node.children.len(), 6);
+
+        let mut node = create_node(&[axis_bit(0) | axis_bit(1); 6]);
+        let off_split = overlap_free_split(&mut node, &config).unwrap();
+        assert_eq!(node.children.len(), 3);
+        assert_eq!(node.split_history, axis_bit(0));
+        match off_split {
+            RTreeNode::Parent(ref data) => assert_eq!(data.split_history, axis_bit(0)),
+            RTreeNode::Leaf(_) => panic!("Expected a parent node"),
+        }
+
+        // Without a history, all axes are searched
+        let mut node = create_node(&[axis_bit(1), axis_bit(1), 0, axis_bit(1), 0, 0]);
+        assert!(overlap_free_split(&mut node, &config).is_some());
+    }
+
+    fn all_nodes_have_split_history<T: RTreeObject>(root: &ParentNode<T>) -> bool {
+        root.children.iter().all(|child| match child {
+            RTreeNode::Parent(ref data) => {
+                data.split_history != 0 && all_nodes_have_split_history(data)
+            }
+            RTreeNode::Leaf(_) => true,
+        })
+    }
+
+    fn check_insertion<T>(elements: Vec<T>) -> RTree<T, XTreeParams>
+    where
+        T: RTreeObject + PartialEq + Clone,
+    {
+        let mut tree: RTree<T, XTreeParams> = RTree::new_with_params();
+        for element in &elements {
+            tree.insert(element.clone());
+        }
+        tree.root().sanity_check::<XTreeParams>(false);
+        assert_eq!(tree.size(), elements.len());
+        assert_eq!(
+            RTreeIterator::new(tree.root(), SelectAllFunc).count(),
+            elements.len()
+        );
+        for element in &elements {
+            assert!(tree.contains(element));
+        }
+        let mut removal_tree = tree.clone();
+        for element in &elements {
+            assert!(removal_tree.remove(element).is_some());
+        }
+        assert_eq!(removal_tree.size(), 0);
+        tree
+    }
+
+    fn max_inner_node_size<T: RTreeObject>(node: &ParentNode<T>) -> usize {
+        node.children
+            .iter()
+            .map(|child| match child {
+                RTreeNode::Parent(ref data) => data.children.len().max(max_inner_node_size(data)),
+                RTreeNode::Leaf(_) => 0,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
@@ -0,0 +1,876 @@
+use crate::algorithm::rstar::split;
+use crate::algorithm::selection_functions::{
+    SelectAllFunc, SelectAtPointFunction, SelectEqualsFunction, SelectInEnvelopeFuncIntersecting,
+    SelectInEnvelopeFunction, SelectionFunction,
+};
+use crate::envelope::Envelope;
+use crate::node::{ParentNode, RTreeNode};
+use crate::object::{PointDistance, RTreeObject};
+use crate::params::{verify_config, DefaultParams, RTreeConfig, RTreeParams};
+use crate::point::Point;
+use crate::rtree::RTree;
+use arc_swap::ArcSwap;
+use num_traits::Zero;
+use smallvec::SmallVec;
+use std::collections::BinaryHeap;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// A parent node of a [ConcurrentSnapshot].
+///
+/// Nodes are shared between all snapshots that contain them. An update copies only the nodes
+/// along its path from the root, all other nodes are shared with the previous snapshot.
+#[derive(Clone)]
+struct Node<T>
+where
+    T: RTreeObject,
+{
+    envelope: T::Envelope,
+    children: Vec<Entry<T>>,
+}
+
+#[derive(Clone)]
+enum Entry<T>
+where
+    T: RTreeObject,
+{
+    Leaf(T),
+    Parent(Arc<Node<T>>),
+}
+
+impl<T> RTreeObject for Entry<T>
+where
+    T: RTreeObject,
+{
+    type Envelope = T::Envelope;
+
+    fn envelope(&self) -> Self::Envelope {
+        match self {
+            Entry::Leaf(t) => t.envelope(),
+            Entry::Parent(node) => node.envelope,
+        }
+    }
+}
+
+impl<T> Node<T>
+where
+    T: RTreeObject,
+{
+    fn new(children: Vec<Entry<T>>) -> Self {
+        let mut envelope = T::Envelope::new_empty();
+        for child in &children {
+            envelope.merge(&child.envelope());
+        }
+        Node { envelope, children }
+    }
+
+    fn from_parent_node(node: ParentNode<T>) -> Self {
+        let children = node
+            .children
+            .into_iter()
+            .map(|child| match child {
+                RTreeNode::Leaf(t) => Entry::Leaf(t),
+                RTreeNode::Parent(data) => Entry::Parent(Arc::new(Node::from_parent_node(data))),
+            })
+            .collect();
+        Node {
+            envelope: node.envelope,
+            children,
+        }
+    }
+
+    fn to_parent_node(&self) -> ParentNode<T>
+    where
+        T: Clone,
+    {
+        let children = self
+            .children
+            .iter()
+            .map(|child| match child {
+                Entry::Leaf(t) => RTreeNode::Leaf(t.clone()),
+                Entry::Parent(node) => RTreeNode::Parent(node.to_parent_node()),
+            })
+            .collect();
+        ParentNode::new_parent(children)
+    }
+}
+
+/// An immutable version of a [ConcurrentRTree].
+///
+/// A snapshot is never changed by subsequent updates of its tree and can be queried without any
+/// synchronization. Snapshots share all nodes that have not been changed in between.
+///
+/// The most common queries are available directly, [ConcurrentSnapshot::to_rtree] copies the
+/// snapshot into an [RTree] offering all other queries.
+pub struct ConcurrentSnapshot<T, Params = DefaultParams>
+where
+    Params: RTreeParams,
+    T: RTreeObject,
+{
+    root: Arc<Node<T>>,
+    /// The number of parent node levels, a root that only contains leaves has a height of 1.
+    height: usize,
+    size: usize,
+    config: RTreeConfig,
+    _params: PhantomData<fn() -> Params>,
+}
+
+impl<T, Params> ConcurrentSnapshot<T, Params>
+where
+    Params: RTreeParams,
+    T: RTreeObject,
+{
+    /// Returns the number of elements contained in this snapshot.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the configuration of the snapshot's tree.
+    pub fn config(&self) -> &RTreeConfig {
+        &self.config
+    }
+
+    /// Returns an iterator over all elements contained in this snapshot.
+    ///
+    /// The order in which the elements are returned is not specified.
+    pub fn iter(&self) -> SnapshotIterator<'_, T, SelectAllFunc> {
+        self.locate_with_selection_function(SelectAllFunc)
+    }
+
+    /// Returns all elements that are selected by a [SelectionFunction].
+    ///
+    /// See [RTree::locate_with_selection_function].
+    pub fn locate_with_selection_function<S>(&self, function: S) -> SnapshotIterator<'_, T, S>
+    where
+        S: SelectionFunction<T>,
+    {
+        SnapshotIterator::new(&self.root, function)
+    }
+
+    /// Returns all elements contained in an [Envelope].
+    ///
+    /// See [RTree::locate_in_envelope].
+    pub fn locate_in_envelope(
+        &self,
+        envelope: &T::Envelope,
+    ) -> SnapshotIterator<'_, T, SelectInEnvelopeFunction<T>> {
+        self.locate_with_selection_function(SelectInEnvelopeFunction::new(*envelope))
+    }
+
+    /// Returns all elements whose envelope intersects a given [Envelope].
+    ///
+    /// See [RTree::locate_in_envelope_intersecting].
+    pub fn locate_in_envelope_intersecting(
+        &self,
+        envelope: &T::Envelope,
+    ) -> SnapshotIterator<'_, T, SelectInEnvelopeFuncIntersecting<T>> {
+        self.locate_with_selection_function(SelectInEnvelopeFuncIntersecting::new(*envelope))
+    }
+
+    /// Returns all elements that contain a given point.
+    ///
+    /// See [RTree::locate_all_at_point].
+    pub fn locate_all_at_point(
+        &self,
+        point: &<T::Envelope as Envelope>::Point,
+    ) -> SnapshotIterator<'_, T, SelectAtPointFunction<T>>
+    where
+        T: PointDistance,
+    {
+        self.locate_with_selection_function(SelectAtPointFunction::new(*point))
+    }
+
+    /// Returns `true` if this snapshot contains an element that is equal to `t`.
+    pub fn contains(&self, t: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.locate_with_selection_function(SelectEqualsFunction::new(t))
+            .next()
+            .is_some()
+    }
+
+    /// Returns the element that is nearest to a given point.
+    ///
+    /// Returns `None` if the snapshot is empty. See [RTree::nearest_neighbor].
+    pub fn nearest_neighbor(&self, query_point: &<T::Envelope as Envelope>::Point) -> Option<&T>
+    where
+        T: PointDistance,
+    {
+        let mut nodes = BinaryHeap::new();
+        nodes.push(EntryDistance {
+            entry: None,
+            node: &self.root,
+            distance: Zero::zero(),
+        });
+        while let Some(current) = nodes.pop() {
+            if let Some(t) = current.entry {
+                return Some(t);
+            }
+            for child in &current.node.children {
+                nodes.push(match child {
+                    Entry::Leaf(t) => EntryDistance {
+                        entry: Some(t),
+                        node: current.node,
+                        distance: t.distance_2(query_point),
+                    },
+                    Entry::Parent(node) => EntryDistance {
+                        entry: None,
+                        node,
+                        distance: node.envelope.distance_2(query_point),
+                    },
+                });
+            }
+        }
+        None
+    }
+
+    /// Copies this snapshot into a new [RTree].
+    ///
+    /// The tree has the same structure as the snapshot.
+    ///
+    /// # Runtime
+    /// This method runs in `O(n)`.
+    pub fn to_rtree(&self) -> RTree<T, Params>
+    where
+        T: Clone,
+    {
+        let root = if self.size == 0 {
+            ParentNode::new_root(&self.config)
+        } else {
+            self.root.to_parent_node()
+        };
+        RTree::new_from_root(root, self.size, self.config)
+    }
+}
+
+/// A node or element of a [ConcurrentSnapshot] together with its distance to a query point.
+struct EntryDistance<'a, T>
+where
+    T: PointDistance,
+{
+    /// The element, or `None` if `node` is to be unpacked.
+    entry: Option<&'a T>,
+    node: &'a Node<T>,
+    distance: <<T::Envelope as Envelope>::Point as Point>::Scalar,
+}
+
+impl<'a, T> PartialEq for EntryDistance<'a, T>
+where
+    T: PointDistance,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.distance == other.distance
+    }
+}
+
+impl<'a, T> PartialOrd for EntryDistance<'a, T>
+where
+    T: PointDistance,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<::std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a, T> Eq for EntryDistance<'a, T> where T: PointDistance {}
+
+impl<'a, T> Ord for EntryDistance<'a, T>
+where
+    T: PointDistance,
+{
+    fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+        // Inverse comparison creates a min heap
+        other.distance.partial_cmp(&self.distance).unwrap()
+    }
+}
+
+/// Iterates over the elements of a [ConcurrentSnapshot] that are selected by a
+/// [SelectionFunction].
+pub struct SnapshotIterator<'a, T, Func>
+where
+    T: RTreeObject + 'a,
+    Func: SelectionFunction<T>,
+{
+    func: Func,
+    current_entries: SmallVec<[&'a Entry<T>; 24]>,
+}
+
+impl<'a, T, Func> SnapshotIterator<'a, T, Func>
+where
+    T: RTreeObject,
+    Func: SelectionFunction<T>,
+{
+    fn new(root: &'a Node<T>, func: Func) -> Self {
+        let current_entries = if func.should_unpack_parent(&root.envelope) {
+            root.children.iter().collect()
+        } else {
+            SmallVec::new()
+        };
+        SnapshotIterator {
+            func,
+            current_entries,
+        }
+    }
+}
+
+impl<'a, T, Func> Iterator for SnapshotIterator<'a, T, Func>
+where
+    T: RTreeObject,
+    Func: SelectionFunction<T>,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        while let Some(next) = self.current_entries.pop() {
+            match next {
+                Entry::Leaf(t) => {
+                    if self.func.should_unpack_leaf(t) {
+                        return Some(t);
+                    }
+                }
+                Entry::Parent(node) => {
+                    if self.func.should_unpack_parent(&node.envelope) {
+                        self.current_entries.extend(&node.children);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// An r-tree that can be queried from many threads while another thread updates it.
+///
+/// Readers call [ConcurrentRTree::snapshot] to obtain an immutable [ConcurrentSnapshot]. A
+/// snapshot is never changed by subsequent updates, queries on it require no synchronization.
+///
+/// The tree is persistent: Each update copies the nodes along its path from the root and shares
+/// all other nodes with the previous version. The new version is then published with an atomic
+/// pointer swap. Taking a snapshot is lock-free and never waits for an update, holding on to a
+/// snapshot only keeps the nodes alive that have been replaced since.
+///
+/// Insertions choose the child whose envelope requires the least enlargement and split
+/// overflowing nodes with the r*-tree split heuristic, the configured insertion strategy is not
+/// used. Concurrent updates are serialized, only a single update is applied at a time.
+///
+/// # Example
+/// ```
+/// use rstar::ConcurrentRTree;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let tree = Arc::new(ConcurrentRTree::new());
+/// let writer = {
+///     let tree = tree.clone();
+///     thread::spawn(move || {
+///         for i in 0..100 {
+///             tree.insert([i as f64, 0.0]);
+///         }
+///     })
+/// };
+/// let snapshot = tree.snapshot();
+/// // The snapshot is not affected by concurrent updates
+/// let size = snapshot.size();
+/// assert_eq!(snapshot.iter().count(), size);
+///
+/// writer.join().unwrap();
+/// assert_eq!(tree.snapshot().size(), 100);
+/// assert_eq!(tree.remove(&[42.0, 0.0]), Some([42.0, 0.0]));
+/// assert!(!tree.snapshot().contains(&[42.0, 0.0]));
+/// ```
+pub struct ConcurrentRTree<T, Params = DefaultParams>
+where
+    Params: RTreeParams,
+    T: RTreeObject,
+{
+    published: ArcSwap<ConcurrentSnapshot<T, Params>>,
+    writer: Mutex<()>,
+}
+
+impl<T, Params> Default for ConcurrentRTree<T, Params>
+where
+    T: RTreeObject + Clone,
+    Params: RTreeParams,
+{
+    fn default() -> Self {
+        Self::new_with_params()
+    }
+}
+
+impl<T, Params> From<RTree<T, Params>> for ConcurrentRTree<T, Params>
+where
+    T: RTreeObject + Clone,
+    Params: RTreeParams,
+{
+    fn from(mut tree: RTree<T, Params>) -> Self {
+        let config = *tree.config();
+        verify_config::<T>(&config);
+        let root = ::std::mem::replace(tree.root_mut(), ParentNode::new_root(&config));
+        let root = Node::from_parent_node(root);
+        let snapshot = ConcurrentSnapshot {
+            height: height(&root),
+            root: Arc::new(root),
+            size: tree.size(),
+            config,
+            _params: PhantomData,
+        };
+        ConcurrentRTree {
+            published: ArcSwap::from_pointee(snapshot),
+            writer: Mutex::new(()),
+        }
+    }
+}
+
+impl<T> ConcurrentRTree<T>
+where
+    T: RTreeObject + Clone,
+{
+    /// Creates a new, empty concurrent r-tree.
+    ///
+    /// The created r-tree is configured with [default parameters](DefaultParams).
+    pub fn new() -> Self {
+        Self::new_with_params()
+    }
+
+    /// Creates a new concurrent r-tree with some elements already inserted.
+    ///
+    /// See [RTree::bulk_load].
+    pub fn bulk_load(elements: Vec<T>) -> Self {
+        RTree::bulk_load(elements).into()
+    }
+
+    /// Creates a new, empty concurrent r-tree using runtime parameters.
+    ///
+    /// Only the node sizes of the configuration are used: `strategy` and `reinsertion_count`
+    /// have no effect since insertions always choose the least enlargement and split nodes
+    /// with the r*-tree heuristic, see [ConcurrentRTree]. See [RTree::with_config] for more
+    /// information.
+    pub fn with_config(config: RTreeConfig) -> Self {
+        RTree::with_config(config).into()
+    }
+}
+
+impl<T, Params> ConcurrentRTree<T, Params>
+where
+    T: RTreeObject + Clone,
+    Params: RTreeParams,
+{
+    /// Creates a new, empty concurrent r-tree with configurable parameters.
+    ///
+    /// Refer to the [RTreeParams] trait for more information.
+    pub fn new_with_params() -> Self {
+        RTree::new_with_params().into()
+    }
+
+    /// Returns the most recently published version of the tree.
+    ///
+    /// The returned snapshot is not affected by subsequent updates. This method is lock-free.
+    pub fn snapshot(&self) -> Arc<ConcurrentSnapshot<T, Params>> {
+        self.published.load_full()
+    }
+
+    /// Inserts a new element and publishes the updated tree.
+    ///
+    /// # Runtime
+    /// This method runs in `O(log(n))`, copying `O(log(n))` nodes.
+    pub fn insert(&self, t: T) {
+        let _writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let current = self.snapshot();
+        let mut root = current.root.clone();
+        let mut height = current.height;
+        insert(&mut root, &mut height, Entry::Leaf(t), 0, &current.config);
+        self.publish(&current, root, height, current.size + 1);
+    }
+
+    /// Removes an element and publishes the updated tree.
+    ///
+    /// Returns the removed element, see [RTree::remove]. Nothing is published if no element
+    /// was removed.
+    ///
+    /// # Runtime
+    /// This method usually runs in `O(log(n))`, copying `O(log(n))` nodes.
+    pub fn remove(&self, t: &T) -> Option<T>
+    where
+        T: PartialEq,
+    {
+        let _writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let current = self.snapshot();
+        let mut path = Vec::new();
+        if !find_path(&current.root, t, &mut path) {
+            return None;
+        }
+        let config = current.config;
+        let mut root = current.root.clone();
+        let mut height = current.height;
+        let mut orphans = Vec::new();
+        let removed = remove_along_path(&mut root, &path, height, &config, &mut orphans);
+        for (entry, entry_height) in orphans {
+            insert(&mut root, &mut height, entry, entry_height, &config);
+        }
+        // Shrink the tree if its root has only a single child node left
+        while height > 1 && root.children.len() == 1 {
+            root = match Arc::make_mut(&mut root).children.pop() {
+                Some(Entry::Parent(child)) => child,
+                _ => unreachable!(),
+            };
+            height -= 1;
+        }
+        self.publish(&current, root, height, current.size - 1);
+        Some(removed)
+    }
+
+    fn publish(
+        &self,
+        current: &ConcurrentSnapshot<T, Params>,
+        root: Arc<Node<T>>,
+        height: usize,
+        size: usize,
+    ) {
+        self.published.store(Arc::new(ConcurrentSnapshot {
+            root,
+            height,
+            size,
+            config: current.config,
+            _params: PhantomData,
+        }));
+    }
+}
+
+fn height<T>(node: &Node<T>) -> usize
+where
+    T: RTreeObject,
+{
+    match node.children.first() {
+        Some(Entry::Parent(child)) => 1 + height(child),
+        _ => 1,
+    }
+}
+
+/// Inserts an entry into the tree, entries of height 0 are elements.
+///
+/// Copies all shared nodes along the insertion path.
+fn insert<T>(
+    root: &mut Arc<Node<T>>,
+    height: &mut usize,
+    entry: Entry<T>,
+    entry_height: usize,
+    config: &RTreeConfig,
+) where
+    T: RTreeObject + Clone,
+{
+    if let Some(off_split) = insert_recursive(root, *height, entry, entry_height, config) {
+        let old_root = ::std::mem::replace(root, Arc::new(Node::new(Vec::new())));
+        *root = Arc::new(Node::new(vec![
+            Entry::Parent(old_root),
+            Entry::Parent(off_split),
+        ]));
+        *height += 1;
+    }
+}
+
+/// Returns the node that has been split off `node` if `node` overflows.
+fn insert_recursive<T>(
+    node: &mut Arc<Node<T>>,
+    height: usize,
+    entry: Entry<T>,
+    entry_height: usize,
+    config: &RTreeConfig,
+) -> Option<Arc<Node<T>>>
+where
+    T: RTreeObject + Clone,
+{
+    let node = Arc::make_mut(node);
+    let envelope = entry.envelope();
+    node.envelope.merge(&envelope);
+    if height == entry_height + 1 {
+        node.children.push(entry);
+    } else {
+        let index = choose_subtree(&node.children, &envelope);
+        let off_split = match &mut node.children[index] {
+            Entry::Parent(child) => {
+                insert_recursive(child, height - 1, entry, entry_height, config)
+            }
+            Entry::Leaf(_) => unreachable!(),
+        };
+        node.children.extend(off_split.map(Entry::Parent));
+    }
+    if node.children.len() > config.max_size {
+        let children = ::std::mem::take(&mut node.children);
+        let mut split_node =
+            ParentNode::new_parent(children.into_iter().map(RTreeNode::Leaf).collect());
+        let off_split = match split(&mut split_node, config) {
+            RTreeNode::Parent(data) => data,
+            RTreeNode::Leaf(_) => unreachable!(),
+        };
+        *node = Node::new(unwrap_entries(split_node));
+        return Some(Arc::new(Node::new(unwrap_entries(off_split))));
+    }
+    None
+}
+
+/// Returns the children of a node that has been built to split entries.
+fn unwrap_entries<T>(node: ParentNode<Entry<T>>) -> Vec<Entry<T>>
+where
+    T: RTreeObject,
+{
+    node.children
+        .into_iter()
+        .map(|child| match child {
+            RTreeNode::Leaf(entry) => entry,
+            RTreeNode::Parent(_) => unreachable!(),
+        })
+        .collect()
+}
+
+/// Returns the index of the child whose envelope requires the least enlargement to include
+/// `envelope`. Ties are resolved by choosing the smallest child.
+fn choose_subtree<T>(children: &[Entry<T>], envelope: &T::Envelope) -> usize
+where
+    T: RTreeObject,
+{
+    let mut best_index = 0;
+    let mut best = None;
+    for (index, child) in children.iter().enumerate() {
+        let child_envelope = child.envelope();
+        let area = child_envelope.area();
+        let enlargement = child_envelope.merged(envelope).area() - area;
+        let value = (enlargement, area);
+        if best.map(|best| value < best).unwrap_or(true) {
+            best = Some(value);
+            best_index = index;
+        }
+    }
+    best_index
+}
+
+/// Searches an element equal to `t` and stores the child indices leading to it in `path`.
+fn find_path<T>(node: &Node<T>, t: &T, path: &mut Vec<usize>) -> bool
+where
+    T: RTreeObject + PartialEq,
+{
+    let envelope = t.envelope();
+    for (index, child) in node.children.iter().enumerate() {
+        path.push(index);
+        let found = match child {
+            Entry::Leaf(leaf) => leaf == t,
+            Entry::Parent(data) => {
+                data.envelope.contains_envelope(&envelope) && find_path(data, t, path)
+            }
+        };
+        if found {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
+
+/// Removes the element at the end of `path`.
+///
+/// Nodes that underflow are removed, their children are added to `orphans` along with their
+/// height and must be reinserted.
+fn remove_along_path<T>(
+    node: &mut Arc<Node<T>>,
+    path: &[usize],
+    height: usize,
+    config: &RTreeConfig,
+    orphans: &mut Vec<(Entry<T>, usize)>,
+) -> T
+where
+    T: RTreeObject + Clone,
+{
+    let node = Arc::make_mut(node);
+    let (&index, path) = path.split_first().unwrap();
+    let removed = if path.is_empty() {
+        match node.children.swap_remove(index) {
+            Entry::Leaf(t) => t,
+            Entry::Parent(_) => unreachable!(),
+        }
+    } else {
+        let (removed, underflow) = match &mut node.children[index] {
+            Entry::Parent(child) => {
+                let removed = remove_along_path(child, path, height - 1, config, orphans);
+                (removed, child.children.len() < config.min_size)
+            }
+            Entry::Leaf(_) => unreachable!(),
+        };
+        if underflow {
+            if let Entry::Parent(child) = node.children.swap_remove(index) {
+                let child = Arc::try_unwrap(child).unwrap_or_else(|child| (*child).clone());
+                orphans.extend(child.children.into_iter().map(|entry| (entry, height - 2)));
+            }
+        }
+        removed
+    };
+    *node = Node::new(::std::mem::take(&mut node.children));
+    removed
+}
+
+#[cfg(test)]
+mod test {
+    use super::ConcurrentRTree;
+    use crate::test_utilities::{create_random_points, SEED_1, SEED_2};
+    use crate::{PointDistance, RTree, RTreeConfig, AABB};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_snapshots_are_not_changed_by_updates() {
+        let points = create_random_points(200, SEED_1);
+        let tree = ConcurrentRTree::bulk_load(points[..100].to_vec());
+        let before = tree.snapshot();
+        for point in &points[100..] {
+            tree.insert(*point);
+        }
+        for point in &points[..50] {
+            assert_eq!(tree.remove(point), Some(*point));
+        }
+        assert_eq!(tree.remove(&points[0]), None);
+
+        assert_eq!(before.size(), 100);
+        before.to_rtree().check_invariants();
+        let after = tree.snapshot();
+        after.to_rtree().check_invariants();
+        assert_eq!(after.size(), 150);
+        for point in &points[..50] {
+            assert!(before.contains(point));
+            assert!(!after.contains(point));
+        }
+        for point in &points[50..] {
+            assert!(after.contains(point));
+        }
+    }
+
+    #[test]
+    fn test_updates_while_holding_snapshots() {
+        let points = create_random_points(100, SEED_1);
+        let tree = ConcurrentRTree::new();
+        let mut snapshots = Vec::new();
+        for (index, point) in points.iter().enumerate() {
+            tree.insert(*point);
+            if index % 3 == 0 {
+                snapshots.push(tree.snapshot());
+            }
+        }
+        for (index, snapshot) in snapshots.iter().enumerate() {
+            snapshot.to_rtree().check_invariants();
+            assert_eq!(snapshot.size(), 3 * index + 1);
+            assert_eq!(snapshot.iter().count(), snapshot.size());
+        }
+        let snapshot = tree.snapshot();
+        snapshot.to_rtree().check_invariants();
+        assert_eq!(snapshot.size(), points.len());
+        for point in &points {
+            assert!(snapshot.contains(point));
+        }
+    }
+
+    #[test]
+    fn test_updates_copy_only_their_path() {
+        let tree = ConcurrentRTree::new();
+        for point in create_random_points(1000, SEED_1) {
+            tree.insert(point);
+        }
+        let before = tree.snapshot();
+        tree.insert([0.5, 0.5]);
+        let after = tree.snapshot();
+        // All but one child of the root are shared with the previous snapshot
+        let shared = before
+            .root
+            .children
+            .iter()
+            .filter(|child| {
+                after
+                    .root
+                    .children
+                    .iter()
+                    .any(|other| match (child, other) {
+                        (super::Entry::Parent(l), super::Entry::Parent(r)) => Arc::ptr_eq(l, r),
+                        _ => false,
+                    })
+            })
+            .count();
+        assert_eq!(shared, before.root.children.len() - 1);
+    }
+
+    #[test]
+    fn test_random_updates() {
+        let config = RTreeConfig {
+            min_size: 2,
+            max_size: 5,
+            ..Default::default()
+        };
+        let points = create_random_points(1000, SEED_1);
+        let tree = ConcurrentRTree::with_config(config);
+        let mut expected = RTree::new();
+        for (index, point) in points.iter().enumerate() {
+            tree.insert(*point);
+            expected.insert(*point);
+            if index % 3 == 0 {
+                let removed = points[index / 3];
+                assert_eq!(tree.remove(&removed), expected.remove(&removed));
+            }
+        }
+        let snapshot = tree.snapshot();
+        let rtree = snapshot.to_rtree();
+        rtree.check_invariants();
+        assert!(rtree
+            .root()
+            .sanity_check_with_sizes(None, Some(config.max_size))
+            .is_some());
+        assert_eq!(snapshot.size(), expected.size());
+        for point in &expected {
+            assert!(snapshot.contains(point));
+        }
+        for query in create_random_points(50, SEED_2) {
+            let found = snapshot.nearest_neighbor(&query).unwrap();
+            let nearest = expected.nearest_neighbor(&query).unwrap();
+            assert_eq!(found.distance_2(&query), nearest.distance_2(&query));
+        }
+        let envelope = AABB::from_corners([0.2, 0.2], [0.6, 0.7]);
+        assert_eq!(
+            snapshot.locate_in_envelope(&envelope).count(),
+            expected.locate_in_envelope(&envelope).count()
+        );
+
+        for point in &points {
+            tree.remove(point);
+        }
+        let empty = tree.snapshot();
+        assert_eq!(empty.size(), 0);
+        assert_eq!(empty.iter().count(), 0);
+        assert_eq!(empty.nearest_neighbor(&[0.0, 0.0]), None);
+        empty.to_rtree().check_invariants();
+    }
+
+    #[test]
+    fn test_concurrent_readers() {
+        const SIZE: usize = 1000;
+        let points = create_random_points(SIZE, SEED_1);
+        let tree = Arc::new(ConcurrentRTree::new());
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let tree = tree.clone();
+                thread::spawn(move || {
+                    let mut last_size = 0;
+                    while last_size < SIZE {
+                        let snapshot = tree.snapshot();
+                        assert!(snapshot.size() >= last_size);
+                        assert_eq!(snapshot.iter().count(), snapshot.size());
+                        last_size = snapshot.size();
+                    }
+                })
+            })
+            .collect();
+        for point in &points {
+            tree.insert(*point);
+        }
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        let snapshot = tree.snapshot();
+        snapshot.to_rtree().check_invariants();
+        assert_eq!(snapshot.size(), SIZE);
+    }
+}
@@ -47,6 +47,12 @@ pub trait Envelope: Clone + Copy + PartialEq + ::std::fmt::Debug {
     /// Returns the envelope's center point.
     fn center(&self) -> Self::Point;
 
+    /// Returns the point with the smallest coordinate value of the envelope in each dimension.
+    fn lower(&self) -> Self::Point;
+
+    /// Returns the point with the largest coordinate value of the envelope in each dimension.
+    fn upper(&self) -> Self::Point;
+
     /// Returns a value proportional to the envelope's perimeter.
     fn perimeter_value(&self) -> <Self::Point as Point>::Scalar;
 
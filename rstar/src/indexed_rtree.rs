@@ -0,0 +1,365 @@
+use crate::envelope::Envelope;
+use crate::object::{PointDistance, RTreeObject};
+use crate::params::{DefaultParams, RTreeConfig, RTreeParams};
+use crate::rtree::RTree;
+use crate::Point;
+
+/// An element of an [IndexedRTree], consisting of an object's index and its cached envelope.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IndexedEnvelope<E> {
+    index: u32,
+    envelope: E,
+}
+
+impl<E> IndexedEnvelope<E> {
+    /// Returns the index of the object within the indexed slice.
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+}
+
+impl<E> RTreeObject for IndexedEnvelope<E>
+where
+    E: Envelope,
+{
+    type Envelope = E;
+
+    fn envelope(&self) -> E {
+        self.envelope
+    }
+}
+
+/// Distances are measured to the cached envelope, not to the indexed object.
+impl<E> PointDistance for IndexedEnvelope<E>
+where
+    E: Envelope,
+{
+    fn distance_2(&self, point: &E::Point) -> <E::Point as Point>::Scalar {
+        self.envelope.distance_2(point)
+    }
+
+    fn contains_point(&self, point: &E::Point) -> bool {
+        self.envelope.contains_point(point)
+    }
+}
+
+/// An r-tree over the objects of a slice that is owned elsewhere.
+///
+/// The tree only stores the index and the envelope of each object, the objects themselves are
+/// never moved or cloned. Queries resolve the found indices back against the slice and return
+/// `(index, object)` pairs. This is useful if the objects are large or already stored in a
+/// container that must not be reordered, e.g. the vertices of a mesh.
+///
+/// Queries that depend on an object's exact geometry, e.g. [IndexedRTree::nearest_neighbor],
+/// use the tree to find candidates by their envelope and check these candidates against the
+/// objects in the slice.
+///
+/// The underlying [RTree] can be accessed with [IndexedRTree::tree]. Since [RTreeObject] is
+/// already implemented for all [Point]s, it can not be implemented for references as well; an
+/// indexed tree serves the same purpose.
+///
+/// # Example
+/// ```
+/// use rstar::primitives::Line;
+/// use rstar::{IndexedRTree, AABB};
+///
+/// let lines = vec![
+///     Line::new([0.0, 0.0], [1.0, 1.0]),
+///     Line::new([2.0, 0.0], [3.0, 0.0]),
+///     Line::new([0.0, 3.0], [3.0, 3.0]),
+/// ];
+/// let tree = IndexedRTree::bulk_load(&lines);
+///
+/// let (index, nearest) = tree.nearest_neighbor(&[2.5, 1.0]).unwrap();
+/// assert_eq!(index, 1);
+/// assert_eq!(nearest, &lines[1]);
+///
+/// let window = AABB::from_corners([-1.0, -1.0], [1.5, 1.5]);
+/// let found: Vec<_> = tree.locate_in_envelope(&window).map(|(index, _)| index).collect();
+/// assert_eq!(found, vec![0]);
+/// ```
+pub struct IndexedRTree<'a, T, Params = DefaultParams>
+where
+    Params: RTreeParams,
+    T: RTreeObject,
+{
+    tree: RTree<IndexedEnvelope<T::Envelope>, Params>,
+    objects: &'a [T],
+}
+
+impl<'a, T> IndexedRTree<'a, T>
+where
+    T: RTreeObject,
+{
+    /// Creates a new indexed r-tree over the given objects.
+    ///
+    /// The tree is bulk loaded, see [RTree::bulk_load].
+    ///
+    /// # Panics
+    /// Panics if the slice contains more than `u32::MAX` objects.
+    pub fn bulk_load(objects: &'a [T]) -> Self {
+        Self::bulk_load_with_params(objects)
+    }
+
+    /// Creates a new indexed r-tree over the given objects using runtime parameters.
+    ///
+    /// For more information refer to [IndexedRTree::bulk_load] and [RTreeConfig].
+    ///
+    /// # Panics
+    /// Panics if the configuration is invalid, see [RTree::with_config], or if the slice
+    /// contains more than `u32::MAX` objects.
+    pub fn bulk_load_with_config(objects: &'a [T], config: RTreeConfig) -> Self {
+        IndexedRTree {
+            tree: RTree::bulk_load_with_config(index_objects(objects), config),
+            objects,
+        }
+    }
+}
+
+impl<'a, T, Params> IndexedRTree<'a, T, Params>
+where
+    T: RTreeObject,
+    Params: RTreeParams,
+{
+    /// Creates a new indexed r-tree over the given objects with configurable parameters.
+    ///
+    /// For more information refer to [IndexedRTree::bulk_load] and [RTreeParams].
+    pub fn bulk_load_with_params(objects: &'a [T]) -> Self {
+        IndexedRTree {
+            tree: RTree::bulk_load_with_params(index_objects(objects)),
+            objects,
+        }
+    }
+
+    /// Returns the number of objects in the tree.
+    pub fn size(&self) -> usize {
+        self.tree.size()
+    }
+
+    /// Returns the indexed objects.
+    pub fn objects(&self) -> &'a [T] {
+        self.objects
+    }
+
+    /// Returns the underlying r-tree.
+    ///
+    /// Its elements are [IndexedEnvelope]s which contain the index and the envelope of an
+    /// object.
+    pub fn tree(&self) -> &RTree<IndexedEnvelope<T::Envelope>, Params> {
+        &self.tree
+    }
+
+    /// Returns an iterator over all indices and objects of the tree.
+    ///
+    /// The order in which the objects are returned is not specified.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &'a T)> + '_ {
+        self.tree.iter().map(self.resolver())
+    }
+
+    /// Returns all objects whose envelope is fully contained in the given envelope.
+    ///
+    /// See [RTree::locate_in_envelope].
+    pub fn locate_in_envelope(
+        &self,
+        envelope: &T::Envelope,
+    ) -> impl Iterator<Item = (usize, &'a T)> + '_ {
+        self.tree.locate_in_envelope(envelope).map(self.resolver())
+    }
+
+    /// Returns all objects whose envelope intersects the given envelope.
+    ///
+    /// See [RTree::locate_in_envelope_intersecting].
+    pub fn locate_in_envelope_intersecting(
+        &self,
+        envelope: &T::Envelope,
+    ) -> impl Iterator<Item = (usize, &'a T)> + '_ {
+        self.tree
+            .locate_in_envelope_intersecting(envelope)
+            .map(self.resolver())
+    }
+
+    fn resolver(&self) -> impl Fn(&IndexedEnvelope<T::Envelope>) -> (usize, &'a T) {
+        let objects = self.objects;
+        move |entry| (entry.index(), &objects[entry.index()])
+    }
+}
+
+impl<'a, T, Params> IndexedRTree<'a, T, Params>
+where
+    T: PointDistance,
+    Params: RTreeParams,
+{
+    /// Returns all objects that contain the given point.
+    ///
+    /// See [RTree::locate_all_at_point].
+    pub fn locate_all_at_point(
+        &self,
+        point: &<T::Envelope as Envelope>::Point,
+    ) -> impl Iterator<Item = (usize, &'a T)> + '_ {
+        let point = *point;
+        self.tree
+            .locate_all_at_point(&point)
+            .map(self.resolver())
+            .filter(move |(_, object)| object.contains_point(&point))
+    }
+
+    /// Returns all objects within a squared distance of the given point.
+    ///
+    /// See [RTree::locate_within_distance].
+    pub fn locate_within_distance(
+        &self,
+        query_point: <T::Envelope as Envelope>::Point,
+        max_squared_radius: <<T::Envelope as Envelope>::Point as Point>::Scalar,
+    ) -> impl Iterator<Item = (usize, &'a T)> + '_ {
+        self.tree
+            .locate_within_distance(query_point, max_squared_radius)
+            .map(self.resolver())
+            .filter(move |(_, object)| {
+                object
+                    .distance_2_if_less_or_equal(&query_point, max_squared_radius)
+                    .is_some()
+            })
+    }
+
+    /// Returns the object that is nearest to the given point.
+    ///
+    /// If multiple objects have the same distance, one of them is returned. Returns `None` if
+    /// the tree is empty.
+    pub fn nearest_neighbor(
+        &self,
+        query_point: &<T::Envelope as Envelope>::Point,
+    ) -> Option<(usize, &'a T)> {
+        let mut best: Option<(usize, <<T::Envelope as Envelope>::Point as Point>::Scalar)> = None;
+        // Candidates are visited by the distance of their envelope, which is a lower bound of
+        // their actual distance.
+        for (entry, envelope_distance_2) in
+            self.tree.nearest_neighbor_iter_with_distance_2(query_point)
+        {
+            let index = entry.index();
+            match best {
+                Some((_, best_distance_2)) if envelope_distance_2 >= best_distance_2 => break,
+                Some((_, best_distance_2)) => {
+                    if let Some(distance_2) = self.objects[index]
+                        .distance_2_if_less_or_equal(query_point, best_distance_2)
+                    {
+                        if distance_2 < best_distance_2 {
+                            best = Some((index, distance_2));
+                        }
+                    }
+                }
+                None => best = Some((index, self.objects[index].distance_2(query_point))),
+            }
+        }
+        best.map(|(index, _)| (index, &self.objects[index]))
+    }
+}
+
+fn index_objects<T: RTreeObject>(objects: &[T]) -> Vec<IndexedEnvelope<T::Envelope>> {
+    assert!(
+        objects.len() <= u32::MAX as usize,
+        "Too many objects. An indexed tree can hold at most u32::MAX objects."
+    );
+    objects
+        .iter()
+        .enumerate()
+        .map(|(index, object)| IndexedEnvelope {
+            index: index as u32,
+            envelope: object.envelope(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::IndexedRTree;
+    use crate::test_utilities::*;
+    use crate::{Envelope, PointDistance, RTreeConfig, RTreeObject};
+
+    #[test]
+    fn test_indexed_envelope_queries() {
+        let rectangles = create_random_rectangles(1000, SEED_1);
+        let tree = IndexedRTree::bulk_load(&rectangles);
+        tree.tree().check_invariants();
+        assert_eq!(tree.size(), rectangles.len());
+        let mut indices: Vec<_> = tree.iter().map(|(index, _)| index).collect();
+        indices.sort_unstable();
+        assert!(indices.into_iter().eq(0..rectangles.len()));
+
+        for query in create_random_rectangles(100, SEED_2) {
+            let query = query.envelope();
+            let mut found: Vec<_> = tree
+                .locate_in_envelope_intersecting(&query)
+                .map(|(index, rectangle)| {
+                    assert_eq!(rectangle, &rectangles[index]);
+                    index
+                })
+                .collect();
+            found.sort_unstable();
+            let expected: Vec<_> = (0..rectangles.len())
+                .filter(|index| query.intersects(&rectangles[*index].envelope()))
+                .collect();
+            assert_eq!(found, expected);
+
+            let contained = tree.locate_in_envelope(&query).count();
+            let expected = rectangles
+                .iter()
+                .filter(|rectangle| query.contains_envelope(&rectangle.envelope()))
+                .count();
+            assert_eq!(contained, expected);
+        }
+    }
+
+    #[test]
+    fn test_indexed_nearest_neighbor() {
+        let lines = create_random_lines(1000, SEED_1);
+        let tree = IndexedRTree::bulk_load_with_config(
+            &lines,
+            RTreeConfig {
+                min_size: 4,
+                max_size: 10,
+                ..Default::default()
+            },
+        );
+        for point in create_random_points(200, SEED_2) {
+            let (index, nearest) = tree.nearest_neighbor(&point).unwrap();
+            assert_eq!(nearest, &lines[index]);
+            let min_distance = lines
+                .iter()
+                .map(|line| line.distance_2(&point))
+                .fold(f64::INFINITY, f64::min);
+            assert_eq!(nearest.distance_2(&point), min_distance);
+
+            let mut within: Vec<_> = tree
+                .locate_within_distance(point, 0.01)
+                .map(|(index, _)| index)
+                .collect();
+            within.sort_unstable();
+            let expected: Vec<_> = (0..lines.len())
+                .filter(|index| lines[*index].distance_2(&point) <= 0.01)
+                .collect();
+            assert_eq!(within, expected);
+        }
+    }
+
+    #[test]
+    fn test_indexed_locate_all_at_point() {
+        let points = create_random_points(100, SEED_1);
+        let mut duplicated = points.clone();
+        duplicated.extend_from_slice(&points);
+        let tree = IndexedRTree::bulk_load(&duplicated);
+        let mut found: Vec<_> = tree
+            .locate_all_at_point(&points[7])
+            .map(|(index, _)| index)
+            .collect();
+        found.sort_unstable();
+        assert_eq!(found, vec![7, 107]);
+    }
+
+    #[test]
+    fn test_empty_indexed_tree() {
+        let tree = IndexedRTree::<[f64; 2]>::bulk_load(&[]);
+        assert_eq!(tree.size(), 0);
+        assert_eq!(tree.nearest_neighbor(&[0.0, 0.0]), None);
+        assert_eq!(tree.iter().count(), 0);
+    }
+}
@@ -0,0 +1,434 @@
+use crate::algorithm::selection_functions::SelectionFunction;
+use crate::envelope::Envelope;
+use crate::object::{PointDistance, RTreeObject};
+use crate::params::{DefaultParams, RTreeConfig, RTreeParams};
+use crate::rtree::RTree;
+use crate::Point;
+use std::convert::TryFrom;
+use std::ops::{Deref, DerefMut};
+
+/// A stable handle to an element of a [KeyedRTree].
+///
+/// Keys are returned by [KeyedRTree::insert] and remain valid until the element is removed.
+/// Keys of removed elements are never handed out again by the same tree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ElementKey {
+    index: u32,
+    generation: u32,
+}
+
+/// An element of a [KeyedRTree], consisting of the inserted object and its key.
+#[derive(Clone, Debug)]
+pub struct KeyedObject<T> {
+    key: ElementKey,
+    object: T,
+}
+
+impl<T> KeyedObject<T> {
+    /// Returns the key of this element.
+    pub fn key(&self) -> ElementKey {
+        self.key
+    }
+
+    /// Returns the inserted object.
+    pub fn object(&self) -> &T {
+        &self.object
+    }
+}
+
+impl<T> RTreeObject for KeyedObject<T>
+where
+    T: RTreeObject,
+{
+    type Envelope = T::Envelope;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.object.envelope()
+    }
+}
+
+impl<T> PointDistance for KeyedObject<T>
+where
+    T: PointDistance,
+{
+    fn distance_2(
+        &self,
+        point: &<Self::Envelope as Envelope>::Point,
+    ) -> <<Self::Envelope as Envelope>::Point as Point>::Scalar {
+        self.object.distance_2(point)
+    }
+
+    fn contains_point(&self, point: &<Self::Envelope as Envelope>::Point) -> bool {
+        self.object.contains_point(point)
+    }
+
+    fn distance_2_if_less_or_equal(
+        &self,
+        point: &<Self::Envelope as Envelope>::Point,
+        max_distance_2: <<Self::Envelope as Envelope>::Point as Point>::Scalar,
+    ) -> Option<<<Self::Envelope as Envelope>::Point as Point>::Scalar> {
+        self.object
+            .distance_2_if_less_or_equal(point, max_distance_2)
+    }
+}
+
+#[derive(Clone)]
+struct Slot<E> {
+    generation: u32,
+    envelope: Option<E>,
+}
+
+struct SelectByKeyFunction<E> {
+    envelope: E,
+    key: ElementKey,
+}
+
+impl<T> SelectionFunction<KeyedObject<T>> for SelectByKeyFunction<T::Envelope>
+where
+    T: RTreeObject,
+{
+    fn should_unpack_parent(&self, parent_envelope: &T::Envelope) -> bool {
+        parent_envelope.contains_envelope(&self.envelope)
+    }
+
+    fn should_unpack_leaf(&self, leaf: &KeyedObject<T>) -> bool {
+        leaf.key == self.key
+    }
+}
+
+/// An r-tree that hands out a stable [ElementKey] for every inserted element.
+///
+/// Elements can be retrieved and removed by their key without requiring `PartialEq`. This also
+/// allows to distinguish between several identical elements.
+///
+/// Spatial queries are performed on the underlying [RTree], see [KeyedRTree::tree]. Its elements
+/// are [KeyedObject]s which give access to both the inserted object and its key.
+///
+/// # Example
+/// ```
+/// use rstar::KeyedRTree;
+///
+/// let mut tree = KeyedRTree::new();
+/// let first = tree.insert([0.0, 1.0]);
+/// let second = tree.insert([0.0, 1.0]);
+///
+/// let nearest = tree.tree().nearest_neighbor(&[0.0, 0.0]).unwrap();
+/// assert!(nearest.key() == first || nearest.key() == second);
+///
+/// assert_eq!(tree.remove(second), Some([0.0, 1.0]));
+/// assert_eq!(tree.get(second), None);
+/// assert_eq!(tree.get(first), Some(&[0.0, 1.0]));
+/// ```
+#[derive(Clone)]
+pub struct KeyedRTree<T, Params = DefaultParams>
+where
+    Params: RTreeParams,
+    T: RTreeObject,
+{
+    tree: RTree<KeyedObject<T>, Params>,
+    slots: Vec<Slot<T::Envelope>>,
+    free_slots: Vec<u32>,
+}
+
+impl<T, Params> Default for KeyedRTree<T, Params>
+where
+    T: RTreeObject,
+    Params: RTreeParams,
+{
+    fn default() -> Self {
+        Self::new_with_params()
+    }
+}
+
+impl<T> KeyedRTree<T>
+where
+    T: RTreeObject,
+{
+    /// Creates a new, empty keyed r-tree.
+    ///
+    /// The created r-tree is configured with [default parameters](DefaultParams).
+    pub fn new() -> Self {
+        Self::new_with_params()
+    }
+
+    /// Creates a new, empty keyed r-tree using runtime parameters.
+    ///
+    /// See [RTree::with_config] for more information.
+    pub fn with_config(config: RTreeConfig) -> Self {
+        KeyedRTree {
+            tree: RTree::with_config(config),
+            slots: Vec::new(),
+            free_slots: Vec::new(),
+        }
+    }
+}
+
+impl<T, Params> KeyedRTree<T, Params>
+where
+    T: RTreeObject,
+    Params: RTreeParams,
+{
+    /// Creates a new, empty keyed r-tree with configurable parameters.
+    ///
+    /// Refer to the [RTreeParams] trait for more information.
+    pub fn new_with_params() -> Self {
+        KeyedRTree {
+            tree: RTree::new_with_params(),
+            slots: Vec::new(),
+            free_slots: Vec::new(),
+        }
+    }
+
+    /// Returns the number of elements in the tree.
+    pub fn size(&self) -> usize {
+        self.tree.size()
+    }
+
+    /// Returns the underlying r-tree.
+    ///
+    /// All queries of [RTree] can be used to search for elements. Each returned [KeyedObject]
+    /// contains the found element's key.
+    pub fn tree(&self) -> &RTree<KeyedObject<T>, Params> {
+        &self.tree
+    }
+
+    /// Inserts a new element and returns its key.
+    ///
+    /// # Runtime
+    /// This method runs in `O(log(n))`.
+    pub fn insert(&mut self, object: T) -> ElementKey {
+        let envelope = object.envelope();
+        let key = if let Some(index) = self.free_slots.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.envelope = Some(envelope);
+            ElementKey {
+                index,
+                generation: slot.generation,
+            }
+        } else {
+            let index = u32::try_from(self.slots.len()).expect("Too many elements in KeyedRTree");
+            self.slots.push(Slot {
+                generation: 0,
+                envelope: Some(envelope),
+            });
+            ElementKey {
+                index,
+                generation: 0,
+            }
+        };
+        self.tree.insert(KeyedObject { key, object });
+        key
+    }
+
+    /// Returns `true` if the tree contains an element with the given key.
+    pub fn contains_key(&self, key: ElementKey) -> bool {
+        self.envelope_of(key).is_some()
+    }
+
+    /// Returns the element with the given key.
+    ///
+    /// # Runtime
+    /// This method runs in `O(log(n))`.
+    pub fn get(&self, key: ElementKey) -> Option<&T> {
+        let envelope = self.envelope_of(key)?;
+        self.tree
+            .locate_with_selection_function(SelectByKeyFunction { envelope, key })
+            .next()
+            .map(KeyedObject::object)
+    }
+
+    /// Mutable variant of [KeyedRTree::get].
+    ///
+    /// The element is taken out of the tree while the returned guard is alive and is re-inserted
+    /// with the same key when the guard is dropped. Hence the element may be changed freely,
+    /// including its envelope.
+    ///
+    /// # Runtime
+    /// This method runs in `O(log(n))`, as does dropping the guard.
+    pub fn get_mut(&mut self, key: ElementKey) -> Option<KeyedRefMut<'_, T, Params>> {
+        let envelope = self.envelope_of(key)?;
+        let keyed = self
+            .tree
+            .remove_with_selection_function(SelectByKeyFunction { envelope, key })?;
+        Some(KeyedRefMut {
+            tree: self,
+            keyed: Some(keyed),
+        })
+    }
+
+    /// Removes and returns the element with the given key.
+    ///
+    /// # Runtime
+    /// This method runs in `O(log(n))`.
+    pub fn remove(&mut self, key: ElementKey) -> Option<T> {
+        let envelope = self.envelope_of(key)?;
+        let removed = self
+            .tree
+            .remove_with_selection_function(SelectByKeyFunction { envelope, key })?;
+        let slot = &mut self.slots[key.index as usize];
+        slot.envelope = None;
+        // A slot whose generations are exhausted is retired, reusing it would revive old keys
+        if slot.generation < u32::MAX {
+            slot.generation += 1;
+            self.free_slots.push(key.index);
+        }
+        Some(removed.object)
+    }
+
+    /// Returns an iterator over all keys and elements of the tree.
+    ///
+    /// The order in which the elements are returned is not specified.
+    pub fn iter(&self) -> impl Iterator<Item = (ElementKey, &T)> {
+        self.tree.iter().map(|keyed| (keyed.key, &keyed.object))
+    }
+
+    fn envelope_of(&self, key: ElementKey) -> Option<T::Envelope> {
+        self.slots
+            .get(key.index as usize)
+            .filter(|slot| slot.generation == key.generation)
+            .and_then(|slot| slot.envelope)
+    }
+}
+
+/// A mutable reference to an element of a [KeyedRTree], returned by [KeyedRTree::get_mut].
+///
+/// The element is re-inserted into the tree when this guard is dropped.
+pub struct KeyedRefMut<'a, T, Params = DefaultParams>
+where
+    Params: RTreeParams,
+    T: RTreeObject,
+{
+    tree: &'a mut KeyedRTree<T, Params>,
+    keyed: Option<KeyedObject<T>>,
+}
+
+impl<'a, T, Params> KeyedRefMut<'a, T, Params>
+where
+    Params: RTreeParams,
+    T: RTreeObject,
+{
+    /// Returns the key of the referenced element.
+    pub fn key(&self) -> ElementKey {
+        self.keyed().key
+    }
+
+    fn keyed(&self) -> &KeyedObject<T> {
+        self.keyed.as_ref().expect("Element is only taken on drop")
+    }
+}
+
+impl<'a, T, Params> Deref for KeyedRefMut<'a, T, Params>
+where
+    Params: RTreeParams,
+    T: RTreeObject,
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.keyed().object
+    }
+}
+
+impl<'a, T, Params> DerefMut for KeyedRefMut<'a, T, Params>
+where
+    Params: RTreeParams,
+    T: RTreeObject,
+{
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self
+            .keyed
+            .as_mut()
+            .expect("Element is only taken on drop")
+            .object
+    }
+}
+
+impl<'a, T, Params> Drop for KeyedRefMut<'a, T, Params>
+where
+    Params: RTreeParams,
+    T: RTreeObject,
+{
+    fn drop(&mut self) {
+        if let Some(keyed) = self.keyed.take() {
+            self.tree.slots[keyed.key.index as usize].envelope = Some(keyed.envelope());
+            self.tree.tree.insert(keyed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::KeyedRTree;
+    use crate::test_utilities::{create_random_points, SEED_1};
+
+    #[test]
+    fn test_insert_and_remove_by_key() {
+        const SIZE: usize = 1000;
+        let points = create_random_points(SIZE, SEED_1);
+        let mut tree = KeyedRTree::new();
+        let keys: Vec<_> = points.iter().map(|p| tree.insert(*p)).collect();
+        assert_eq!(tree.size(), SIZE);
+        for (key, point) in keys.iter().zip(&points) {
+            assert_eq!(tree.get(*key), Some(point));
+        }
+        for (key, point) in keys.iter().zip(&points).step_by(2) {
+            assert_eq!(tree.remove(*key), Some(*point));
+            assert_eq!(tree.remove(*key), None);
+            assert!(!tree.contains_key(*key));
+        }
+        assert_eq!(tree.size(), SIZE / 2);
+        for (key, point) in keys.iter().zip(&points).skip(1).step_by(2) {
+            assert_eq!(tree.get(*key), Some(point));
+        }
+    }
+
+    #[test]
+    fn test_identical_elements() {
+        let mut tree = KeyedRTree::new();
+        let first = tree.insert([1, 2]);
+        let second = tree.insert([1, 2]);
+        assert_ne!(first, second);
+        assert_eq!(tree.remove(first), Some([1, 2]));
+        assert!(tree.contains_key(second));
+        assert_eq!(tree.tree().iter().next().map(|k| k.key()), Some(second));
+
+        // Reused slots must not revive old keys
+        let third = tree.insert([3, 4]);
+        assert_ne!(first, third);
+        assert_eq!(tree.get(first), None);
+        assert_eq!(tree.get(third), Some(&[3, 4]));
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let mut tree = KeyedRTree::new();
+        let key = tree.insert([0.0, 1.0]);
+        let other = tree.insert([5.0, 5.0]);
+        assert_eq!(tree.get_mut(key).as_deref(), Some(&[0.0, 1.0]));
+        assert_eq!(tree.iter().count(), 2);
+
+        // Changing the envelope must not invalidate the key
+        *tree.get_mut(key).unwrap() = [10.0, -3.0];
+        assert_eq!(tree.get(key), Some(&[10.0, -3.0]));
+        assert_eq!(tree.get(other), Some(&[5.0, 5.0]));
+        let nearest = tree.tree().nearest_neighbor(&[10.0, -3.0]).unwrap();
+        assert_eq!(nearest.key(), key);
+        assert_eq!(tree.size(), 2);
+        assert_eq!(tree.remove(key), Some([10.0, -3.0]));
+        assert!(tree.get_mut(key).is_none());
+    }
+
+    #[test]
+    fn test_exhausted_slots_are_retired() {
+        let mut tree = KeyedRTree::new();
+        let key = tree.insert([0, 0]);
+        tree.remove(key);
+        tree.slots[key.index as usize].generation = u32::MAX;
+        let old_key = tree.insert([1, 1]);
+        assert_eq!(old_key.index, key.index);
+        assert_eq!(tree.remove(old_key), Some([1, 1]));
+        let new_key = tree.insert([1, 1]);
+        assert_ne!(new_key.index, old_key.index);
+        assert_eq!(tree.get(old_key), None);
+    }
+}
@@ -19,28 +19,56 @@
 //! # (De)Serialization
 //! Enable the `serde` feature for [Serde](https://crates.io/crates/serde) support.
 //!
+//! # Parallelism
+//! Enable the `rayon` feature for parallel bulk loading with `RTree::bulk_load_parallel` and
+//! parallel queries, e.g. `RTree::par_locate_in_envelope`.
+//!
+//! Enable the `arc-swap` feature for `ConcurrentRTree`, which can be queried from many threads
+//! while it is being updated.
+//!
 #![deny(missing_docs)]
 #![forbid(unsafe_code)]
 
 mod aabb;
 mod algorithm;
+#[cfg(feature = "arc-swap")]
+mod concurrent_rtree;
 mod envelope;
+mod indexed_rtree;
+mod keyed_rtree;
 mod node;
 mod object;
 mod params;
 mod point;
 pub mod primitives;
 mod rtree;
+mod tuning;
 
 #[cfg(test)]
 mod test_utilities;
 
 pub use crate::aabb::AABB;
+pub use crate::algorithm::bulk_load::{
+    BulkLoadAlgorithm, ExternalBulkLoader, ExternalElement, ExternalRTree, SortedElements,
+};
+pub use crate::algorithm::guttman::{
+    GuttmanLinearInsertionStrategy, GuttmanQuadraticInsertionStrategy,
+};
+pub use crate::algorithm::hilbert::HilbertInsertionStrategy;
+pub use crate::algorithm::rrstar::RRStarInsertionStrategy;
 pub use crate::algorithm::rstar::RStarInsertionStrategy;
 pub use crate::algorithm::selection_functions::SelectionFunction;
+pub use crate::algorithm::xtree::XTreeInsertionStrategy;
+#[cfg(feature = "arc-swap")]
+pub use crate::concurrent_rtree::{ConcurrentRTree, ConcurrentSnapshot};
 pub use crate::envelope::Envelope;
+pub use crate::indexed_rtree::{IndexedEnvelope, IndexedRTree};
+pub use crate::keyed_rtree::{ElementKey, KeyedObject, KeyedRTree, KeyedRefMut};
 pub use crate::node::{ParentNode, RTreeNode};
 pub use crate::object::{PointDistance, RTreeObject};
-pub use crate::params::{DefaultParams, InsertionStrategy, RTreeParams};
+pub use crate::params::{
+    DefaultParams, InsertionStrategy, InsertionStrategyKind, RTreeConfig, RTreeParams,
+};
 pub use crate::point::{Point, RTreeNum};
 pub use crate::rtree::RTree;
+pub use crate::tuning::{ParameterTuner, TuningQuery, TuningResult};
@@ -1,5 +1,7 @@
 use crate::envelope::Envelope;
 use crate::object::RTreeObject;
+use crate::params::RTreeConfig;
+#[cfg(test)]
 use crate::params::RTreeParams;
 
 #[cfg(feature = "serde")]
@@ -40,6 +42,10 @@ where
 {
     pub(crate) children: Vec<RTreeNode<T>>,
     pub(crate) envelope: T::Envelope,
+    /// The axes along which this node has been split, one bit per axis. Only recorded by
+    /// insertion strategies that make use of it and not serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) split_history: u64,
 }
 
 impl<T> RTreeObject for RTreeNode<T>
@@ -83,72 +89,199 @@ where
         self.envelope
     }
 
-    pub(crate) fn new_root<Params>() -> Self
-    where
-        Params: RTreeParams,
-    {
+    pub(crate) fn new_root(config: &RTreeConfig) -> Self {
         ParentNode {
             envelope: Envelope::new_empty(),
-            children: Vec::with_capacity(Params::MAX_SIZE + 1),
+            children: Vec::with_capacity(config.max_size + 1),
+            split_history: 0,
         }
     }
 
-    pub(crate) fn new_parent(children: Vec<RTreeNode<T>>) -> Self {
+    /// Creates a new parent node containing the given children.
+    pub fn new_parent(children: Vec<RTreeNode<T>>) -> Self {
         let envelope = envelope_for_children(&children);
 
-        ParentNode { envelope, children }
+        ParentNode {
+            envelope,
+            children,
+            split_history: 0,
+        }
+    }
+
+    /// Returns this node's children for modification.
+    ///
+    /// Modifying the children may invalidate the envelope of this node. Call
+    /// [ParentNode::update_envelope] afterwards to repair it.
+    pub fn children_mut(&mut self) -> &mut [RTreeNode<T>] {
+        &mut self.children
+    }
+
+    /// Adds a child to this node and enlarges its envelope accordingly.
+    pub fn push_child(&mut self, child: RTreeNode<T>) {
+        self.envelope.merge(&child.envelope());
+        self.children.push(child);
+    }
+
+    /// Removes and returns the child at position `index`, shifting all children after it.
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove_child(&mut self, index: usize) -> RTreeNode<T> {
+        let child = self.children.remove(index);
+        self.update_envelope();
+        child
+    }
+
+    /// Removes all children from this node and returns them.
+    pub fn take_children(&mut self) -> Vec<RTreeNode<T>> {
+        self.envelope = T::Envelope::new_empty();
+        ::std::mem::take(&mut self.children)
+    }
+
+    /// Splits this node into two. The children from position `at` onwards are moved into a new
+    /// node which is returned.
+    ///
+    /// Panics if `at` is larger than the number of children.
+    pub fn split_off(&mut self, at: usize) -> ParentNode<T> {
+        let off_split = self.children.split_off(at);
+        self.update_envelope();
+        ParentNode::new_parent(off_split)
+    }
+
+    /// Recalculates this node's envelope from the envelopes of its children.
+    ///
+    /// The envelopes of the children themselves are not updated, see
+    /// [RTree::refit_envelopes](crate::RTree::refit_envelopes) for updating a whole tree.
+    pub fn update_envelope(&mut self) {
+        self.envelope = envelope_for_children(&self.children);
+    }
+
+    /// Returns the index of the child whose envelope requires the least area enlargement to
+    /// include `envelope`. Ties are resolved by choosing the child with the smallest area.
+    ///
+    /// Returns `None` if this node contains leaves or no children at all.
+    pub fn choose_child(&self, envelope: &T::Envelope) -> Option<usize> {
+        crate::algorithm::guttman::choose_subtree(self, envelope)
+    }
+
+    /// Inserts a node at the given depth below this node, which must be the tree's root.
+    ///
+    /// `depth` specifies the depth of the parent that receives the node, a depth of 0 refers to
+    /// the root itself. The receiving parent is chosen according to the r* heuristic and
+    /// overflowing nodes are split without any reinsertion. This is useful for reinserting
+    /// nodes that were removed to resolve an overflow.
+    ///
+    /// Returns `true` if the root was split and the tree's height has thus increased by one.
+    pub fn insert_at_depth(
+        &mut self,
+        node: RTreeNode<T>,
+        depth: usize,
+        config: &RTreeConfig,
+    ) -> bool {
+        crate::algorithm::rstar::insert_at_depth(self, node, depth, config)
+    }
+
+    /// Recalculates the envelopes of this node and all of its descendants.
+    ///
+    /// Returns the node's new envelope.
+    pub(crate) fn refit_envelopes(&mut self) -> T::Envelope {
+        let mut envelope = T::Envelope::new_empty();
+        for child in &mut self.children {
+            match child {
+                RTreeNode::Leaf(ref t) => envelope.merge(&t.envelope()),
+                RTreeNode::Parent(ref mut data) => envelope.merge(&data.refit_envelopes()),
+            }
+        }
+        self.envelope = envelope;
+        envelope
+    }
+
+    /// Checks the structural invariants of the (sub)tree rooted at this node and returns the
+    /// number of leaves.
+    ///
+    /// Panics if any envelope differs from the envelope of its children, if leaves are found at
+    /// different depths or if a parent node other than this node has no children.
+    pub(crate) fn check_invariants(&self) -> usize {
+        let mut leaf_height = None;
+        self.check_invariants_inner(None, None, 1, &mut leaf_height)
     }
 
     #[cfg(test)]
-    pub fn sanity_check<Params>(&self, check_max_size: bool) -> Option<usize>
+    pub(crate) fn sanity_check<Params>(&self, check_max_size: bool) -> Option<usize>
     where
         Params: RTreeParams,
     {
+        let max_size = if check_max_size {
+            Some(Params::MAX_SIZE)
+        } else {
+            None
+        };
+        self.sanity_check_with_sizes(Some(Params::MIN_SIZE), max_size)
+    }
+
+    /// Checks envelopes and leaf heights but ignores node sizes, as required for
+    /// bulk loaded trees.
+    #[cfg(test)]
+    pub(crate) fn sanity_check_unsized(&self) -> Option<usize> {
+        self.sanity_check_with_sizes(None, None)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn sanity_check_with_sizes(
+        &self,
+        min_size: Option<usize>,
+        max_size: Option<usize>,
+    ) -> Option<usize> {
         if self.children.is_empty() {
             Some(0)
         } else {
             let mut result = None;
-            self.sanity_check_inner::<Params>(check_max_size, 1, &mut result);
+            self.check_invariants_inner(min_size, max_size, 1, &mut result);
             result
         }
     }
 
-    #[cfg(test)]
-    fn sanity_check_inner<Params>(
+    fn check_invariants_inner(
         &self,
-        check_max_size: bool,
+        min_size: Option<usize>,
+        max_size: Option<usize>,
         height: usize,
         leaf_height: &mut Option<usize>,
-    ) where
-        Params: RTreeParams,
-    {
+    ) -> usize {
         if height > 1 {
-            let min_size = Params::MIN_SIZE;
-            assert!(self.children.len() >= min_size);
+            assert!(!self.children.is_empty(), "Found an empty parent node");
+            if let Some(min_size) = min_size {
+                assert!(self.children.len() >= min_size);
+            }
         }
-        let mut envelope = T::Envelope::new_empty();
-        if check_max_size {
-            let max_size = Params::MAX_SIZE;
+        if let Some(max_size) = max_size {
             assert!(self.children.len() <= max_size);
         }
 
+        let mut envelope = T::Envelope::new_empty();
+        let mut leaf_count = 0;
         for child in &self.children {
             match child {
                 RTreeNode::Leaf(ref t) => {
                     envelope.merge(&t.envelope());
+                    leaf_count += 1;
                     if let Some(ref leaf_height) = leaf_height {
-                        assert_eq!(height, *leaf_height);
+                        assert_eq!(height, *leaf_height, "Found leaves at different depths");
                     } else {
                         *leaf_height = Some(height);
                     }
                 }
                 RTreeNode::Parent(ref data) => {
                     envelope.merge(&data.envelope);
-                    data.sanity_check_inner::<Params>(check_max_size, height + 1, leaf_height);
+                    leaf_count +=
+                        data.check_invariants_inner(min_size, max_size, height + 1, leaf_height);
                 }
             }
         }
-        assert_eq!(self.envelope, envelope);
+        assert_eq!(
+            self.envelope, envelope,
+            "Found a node whose envelope differs from its children's envelope"
+        );
+        leaf_count
     }
 }
 
@@ -1,3 +1,4 @@
+use crate::algorithm::bulk_load::div_up;
 use crate::algorithm::rstar::RStarInsertionStrategy;
 use crate::{Envelope, Point, RTree, RTreeObject};
 
@@ -5,6 +6,7 @@ use crate::{Envelope, Point, RTree, RTreeObject};
 ///
 /// Internally, an r-tree contains several nodes, similar to a b-tree. These parameters change
 /// the size of these nodes and can be used to fine-tune the tree's performance.
+/// Use [RTreeConfig] instead if the parameters are only known at runtime.
 ///
 /// # Example
 /// ```
@@ -50,6 +52,13 @@ pub trait RTreeParams: Send + Sync {
     /// Larger values will improve query times but increase insertion time.
     const REINSERTION_COUNT: usize;
 
+    /// The percentage of `MAX_SIZE` that bulk loading fills each node with. Must be larger than
+    /// `0` and at most `100`.
+    /// Nodes that are completely filled after bulk loading must be split as soon as an element
+    /// is inserted into them. Lower values leave room for subsequent insertions at the cost of
+    /// a slightly larger tree. Defaults to `100`.
+    const BULK_LOAD_FILL_FACTOR: u8 = 100;
+
     /// The insertion strategy which is used when calling [RTree::insert].
     type DefaultInsertionStrategy: InsertionStrategy;
 }
@@ -70,40 +79,250 @@ impl RTreeParams for DefaultParams {
 /// Different strategies try to minimize both _insertion time_ (how long does it take to add a new
 /// object into the tree?) and _querying time_ (how long does an average nearest neighbor query
 /// take?).
-/// Currently, only one insertion strategy is implemented: R* (R-star) insertion. R* insertion
-/// tries to minimize querying performance while yielding reasonable insertion times, making it a
-/// good default strategy. More strategies may be implemented in the future.
+/// The following strategies are implemented:
+///  - [RStarInsertionStrategy]: R* (R-star) insertion tries to minimize querying time while
+///    yielding reasonable insertion times, making it a good default strategy.
+///  - [RRStarInsertionStrategy](crate::RRStarInsertionStrategy): The revised R* heuristic
+///    avoids R*'s forced reinsertion and often improves both insertion and query times.
+///  - [HilbertInsertionStrategy](crate::HilbertInsertionStrategy): Orders elements along a
+///    Hilbert curve and defers splits, yielding fuller nodes and thus smaller trees.
+///  - [XTreeInsertionStrategy](crate::XTreeInsertionStrategy): Avoids overlapping nodes in high
+///    dimensions by creating supernodes.
+///  - [GuttmanQuadraticInsertionStrategy](crate::GuttmanQuadraticInsertionStrategy) and
+///    [GuttmanLinearInsertionStrategy](crate::GuttmanLinearInsertionStrategy): Guttman's
+///    original heuristics insert considerably faster but yield trees with slower queries.
 ///
 /// Only calls to [RTree::insert] are affected by this strategy.
 ///
-/// This trait is not meant to be implemented by the user.
+/// # Implementing custom strategies
+/// Custom strategies can modify the tree's nodes through [RTree::root_mut].
+/// [ParentNode](crate::ParentNode) provides operations to choose a child, add, remove and split
+/// children and to update a node's envelope. A strategy must keep all leaves at the same depth
+/// and all envelopes up to date, which can be verified with [RTree::check_invariants]. Enabling
+/// the `debug` feature performs this check after every insertion. Node sizes should be read from
+/// [RTree::config] to support trees with [runtime parameters](RTreeConfig).
+///
+/// ```
+/// use rstar::{InsertionStrategy, ParentNode, RTree, RTreeNode, RTreeObject, RTreeParams};
+///
+/// /// Splits overflowing nodes in the middle.
+/// enum HalvingInsertionStrategy {}
+///
+/// impl InsertionStrategy for HalvingInsertionStrategy {
+///     fn insert<T, Params>(tree: &mut RTree<T, Params>, t: T)
+///     where
+///         Params: RTreeParams,
+///         T: RTreeObject,
+///     {
+///         let max_size = tree.config().max_size;
+///         let root = tree.root_mut();
+///         if let Some(off_split) = insert_recursive(root, RTreeNode::Leaf(t), max_size) {
+///             // The root was split, grow the tree
+///             let old_root = std::mem::replace(root, ParentNode::new_parent(Vec::new()));
+///             root.push_child(RTreeNode::Parent(old_root));
+///             root.push_child(RTreeNode::Parent(off_split));
+///         }
+///     }
+/// }
+///
+/// fn insert_recursive<T: RTreeObject>(
+///     node: &mut ParentNode<T>,
+///     child: RTreeNode<T>,
+///     max_size: usize,
+/// ) -> Option<ParentNode<T>> {
+///     if let Some(index) = node.choose_child(&child.envelope()) {
+///         let off_split = match &mut node.children_mut()[index] {
+///             RTreeNode::Parent(parent) => insert_recursive(parent, child, max_size),
+///             RTreeNode::Leaf(_) => unreachable!(),
+///         };
+///         node.update_envelope();
+///         if let Some(off_split) = off_split {
+///             node.push_child(RTreeNode::Parent(off_split));
+///         }
+///     } else {
+///         node.push_child(child);
+///     }
+///     if node.children().len() > max_size {
+///         Some(node.split_off(node.children().len() / 2))
+///     } else {
+///         None
+///     }
+/// }
+///
+/// struct HalvingParams;
+///
+/// impl RTreeParams for HalvingParams {
+///     const MIN_SIZE: usize = 2;
+///     const MAX_SIZE: usize = 6;
+///     const REINSERTION_COUNT: usize = 0;
+///     type DefaultInsertionStrategy = HalvingInsertionStrategy;
+/// }
+///
+/// let mut tree: RTree<_, HalvingParams> = RTree::new_with_params();
+/// for x in 0..100 {
+///     tree.insert([x as f64, 0.5 * x as f64]);
+/// }
+/// tree.check_invariants();
+/// assert_eq!(tree.size(), 100);
+/// assert!(tree.contains(&[42.0, 21.0]));
+/// ```
 pub trait InsertionStrategy {
-    #[doc(hidden)]
+    /// Inserts an element into the tree.
+    ///
+    /// The tree's size is updated by the caller.
     fn insert<T, Params>(tree: &mut RTree<T, Params>, t: T)
     where
         Params: RTreeParams,
         T: RTreeObject;
 }
 
-pub fn verify_parameters<T: RTreeObject, P: RTreeParams>() {
-    assert!(
-        P::MAX_SIZE >= 4,
-        "MAX_SIZE too small. Must be larger than 4."
-    );
+/// Defines the parameters of an r-tree at runtime.
+///
+/// [RTreeParams] fixes a tree's node sizes at compile time. An `RTreeConfig` can be used instead
+/// if these values are only known at runtime, e.g. if they are read from a configuration file
+/// or chosen depending on the data set. See [RTreeParams] for a description of the individual
+/// parameters.
+///
+/// # Example
+/// ```
+/// use rstar::{InsertionStrategyKind, RTree, RTreeConfig};
+///
+/// let config = RTreeConfig {
+///     min_size: 8,
+///     max_size: 24,
+///     reinsertion_count: 6,
+///     strategy: InsertionStrategyKind::RStar,
+///     bulk_load_fill_factor: 70,
+/// };
+/// let mut tree = RTree::with_config(config);
+/// tree.insert([0.0, 1.0]);
+/// assert_eq!(tree.config(), &config);
+///
+/// // Bulk loading respects the configured node size as well
+/// let tree = RTree::bulk_load_with_config(vec![[0.0, 1.0], [2.0, 3.0]], config);
+/// assert_eq!(tree.size(), 2);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RTreeConfig {
+    /// The minimum size of an internal node, see [RTreeParams::MIN_SIZE].
+    pub min_size: usize,
+    /// The maximum size of an internal node, see [RTreeParams::MAX_SIZE].
+    pub max_size: usize,
+    /// The number of nodes to reinsert, see [RTreeParams::REINSERTION_COUNT].
+    pub reinsertion_count: usize,
+    /// The insertion strategy which is used when calling [RTree::insert].
+    pub strategy: InsertionStrategyKind,
+    /// The percentage of `max_size` that bulk loading fills each node with, see
+    /// [RTreeParams::BULK_LOAD_FILL_FACTOR].
+    pub bulk_load_fill_factor: u8,
+}
 
-    let max_min_size = (P::MAX_SIZE + 1) / 2;
-    assert!(
-        P::MIN_SIZE <= max_min_size,
-        "MIN_SIZE too large. Must be less or equal to {:?}",
-        max_min_size
-    );
+impl RTreeConfig {
+    /// Creates a configuration with the values of compile time parameters.
+    ///
+    /// The configuration uses [InsertionStrategyKind::Default], selecting
+    /// `Params::DefaultInsertionStrategy`.
+    pub fn from_params<Params: RTreeParams>() -> Self {
+        RTreeConfig {
+            min_size: Params::MIN_SIZE,
+            max_size: Params::MAX_SIZE,
+            reinsertion_count: Params::REINSERTION_COUNT,
+            strategy: InsertionStrategyKind::Default,
+            bulk_load_fill_factor: Params::BULK_LOAD_FILL_FACTOR,
+        }
+    }
 
-    let max_reinsertion_count = P::MAX_SIZE - P::MIN_SIZE;
-    assert!(
-        P::REINSERTION_COUNT < max_reinsertion_count,
-        "REINSERTION_COUNT too large. Must be smaller than {:?}",
-        max_reinsertion_count
-    );
+    /// Checks if the configuration can be used by an r-tree.
+    ///
+    /// Returns a description of the first violated constraint otherwise. Constructors like
+    /// [RTree::with_config](crate::RTree::with_config) panic with this description.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_size < 4 {
+            return Err("MAX_SIZE too small. Must be larger than 4.".into());
+        }
+        if self.min_size == 0 {
+            return Err("MIN_SIZE too small. Must be at least 1.".into());
+        }
+        let max_min_size = (self.max_size + 1) / 2;
+        if self.min_size > max_min_size {
+            return Err(format!(
+                "MIN_SIZE too large. Must be less or equal to {:?}",
+                max_min_size
+            ));
+        }
+        let max_reinsertion_count = self.max_size - self.min_size;
+        if self.reinsertion_count >= max_reinsertion_count {
+            return Err(format!(
+                "REINSERTION_COUNT too large. Must be smaller than {:?}",
+                max_reinsertion_count
+            ));
+        }
+        if self.bulk_load_fill_factor == 0 || self.bulk_load_fill_factor > 100 {
+            return Err(
+                "BULK_LOAD_FILL_FACTOR out of range. Must be larger than 0 and at most 100.".into(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the number of children that bulk loading puts into each node.
+    pub(crate) fn bulk_load_node_size(&self) -> usize {
+        let node_size = div_up(self.max_size * usize::from(self.bulk_load_fill_factor), 100);
+        node_size.clamp(self.min_size.max(2), self.max_size)
+    }
+
+    /// Returns the configuration that is passed to bulk loading algorithms.
+    ///
+    /// Its `max_size` is reduced to [RTreeConfig::bulk_load_node_size].
+    pub(crate) fn bulk_load_config(&self) -> RTreeConfig {
+        RTreeConfig {
+            max_size: self.bulk_load_node_size(),
+            ..*self
+        }
+    }
+}
+
+impl Default for RTreeConfig {
+    fn default() -> Self {
+        Self::from_params::<DefaultParams>()
+    }
+}
+
+/// Selects the insertion strategy of an [RTreeConfig] at runtime.
+///
+/// See [InsertionStrategy] for an overview of the available strategies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InsertionStrategyKind {
+    /// Uses the tree's `RTreeParams::DefaultInsertionStrategy`.
+    Default,
+    /// Uses [RStarInsertionStrategy].
+    RStar,
+    /// Uses [RRStarInsertionStrategy](crate::RRStarInsertionStrategy).
+    RRStar,
+    /// Uses [GuttmanLinearInsertionStrategy](crate::GuttmanLinearInsertionStrategy).
+    GuttmanLinear,
+    /// Uses [GuttmanQuadraticInsertionStrategy](crate::GuttmanQuadraticInsertionStrategy).
+    GuttmanQuadratic,
+    /// Uses [HilbertInsertionStrategy](crate::HilbertInsertionStrategy).
+    Hilbert,
+    /// Uses [XTreeInsertionStrategy](crate::XTreeInsertionStrategy).
+    XTree,
+}
+
+// Deriving `Default` for enums requires a newer compiler than the supported minimum
+#[allow(clippy::derivable_impls)]
+impl Default for InsertionStrategyKind {
+    fn default() -> Self {
+        InsertionStrategyKind::Default
+    }
+}
+
+pub fn verify_config<T: RTreeObject>(config: &RTreeConfig) {
+    if let Err(message) = config.validate() {
+        panic!("{}", message);
+    }
 
     let dimension = <T::Envelope as Envelope>::Point::DIMENSIONS;
     assert!(
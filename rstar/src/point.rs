@@ -302,6 +302,25 @@ where
     }
 }
 
+/// Converts an integer into an arbitrary scalar type.
+///
+/// Runs in `O(log(value))`, [RTreeNum] does not offer a cheaper conversion.
+pub fn scalar_from_usize<S>(mut value: usize) -> S
+where
+    S: RTreeNum,
+{
+    let mut result = S::zero();
+    let mut power = S::one();
+    while value > 0 {
+        if value & 1 == 1 {
+            result = result + power;
+        }
+        power = power + power;
+        value >>= 1;
+    }
+    result
+}
+
 macro_rules! count_exprs {
     () => (0);
     ($head:expr) => (1);
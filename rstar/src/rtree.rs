@@ -1,15 +1,34 @@
-use crate::algorithm::bulk_load;
+use crate::aabb::AABB;
+use crate::algorithm::bulk_load::{self, BulkLoadAlgorithm};
+use crate::algorithm::graft;
+use crate::algorithm::guttman::{
+    GuttmanLinearInsertionStrategy, GuttmanQuadraticInsertionStrategy,
+};
+use crate::algorithm::hilbert::HilbertInsertionStrategy;
 use crate::algorithm::intersection_iterator::IntersectionIterator;
 use crate::algorithm::iterators::*;
 use crate::algorithm::nearest_neighbor;
+use crate::algorithm::nearest_neighbor_batch;
+#[cfg(feature = "rayon")]
+use crate::algorithm::parallel_iterators::{
+    ParallelIntersectionIterator, ParallelSelectionIterator,
+};
 use crate::algorithm::removal;
+use crate::algorithm::rrstar::RRStarInsertionStrategy;
+use crate::algorithm::rstar::RStarInsertionStrategy;
 use crate::algorithm::selection_functions::*;
+use crate::algorithm::xtree::XTreeInsertionStrategy;
 use crate::envelope::Envelope;
 use crate::node::ParentNode;
 use crate::object::{PointDistance, RTreeObject};
-use crate::params::{verify_parameters, DefaultParams, InsertionStrategy, RTreeParams};
+use crate::params::{
+    verify_config, DefaultParams, InsertionStrategy, InsertionStrategyKind, RTreeConfig,
+    RTreeParams,
+};
 use crate::Point;
 
+#[cfg(feature = "rayon")]
+use rayon::iter::ParallelIterator;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -47,8 +66,9 @@ where
 /// overlap and volume. This is done heuristically. While the originally proposed heuristic focused
 /// on fast insertion operations, the resulting r-trees were often suboptimally structured. Another
 /// heuristic, called `R*-tree` (r-star-tree), was proposed to improve the tree structure at the cost of
-/// longer insertion operations and is currently the crate's only implemented
-/// [insertion strategy].
+/// longer insertion operations and is the crate's default
+/// [insertion strategy]. Both heuristics are available, see [InsertionStrategy] for more
+/// information.
 ///
 /// ## Further reading
 /// For more information refer to the [wikipedia article](https://en.wikipedia.org/wiki/R-tree).
@@ -78,6 +98,9 @@ where
 /// for point in &tree {
 ///     println!("Tree contains a point {:?}", point);
 /// }
+/// // So does RTree, returning the owned elements
+/// let points: Vec<_> = tree.into_iter().collect();
+/// assert_eq!(points.len(), 2);
 /// ```
 ///
 /// ## Supported point types
@@ -145,6 +168,11 @@ where
 {
     root: ParentNode<T>,
     size: usize,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "RTreeConfig::from_params::<Params>")
+    )]
+    config: RTreeConfig,
     _params: ::std::marker::PhantomData<Params>,
 }
 
@@ -206,6 +234,328 @@ where
     pub fn bulk_load(elements: Vec<T>) -> Self {
         Self::bulk_load_with_params(elements)
     }
+
+    /// Creates a new r-tree with some elements already inserted, using a specific bulk loading
+    /// algorithm.
+    ///
+    /// See [BulkLoadAlgorithm] for the available algorithms. [RTree::bulk_load] uses
+    /// [BulkLoadAlgorithm::Omt].
+    ///
+    /// # Example
+    /// ```
+    /// use rstar::{BulkLoadAlgorithm, RTree};
+    ///
+    /// let points: Vec<_> = (0..100).map(|i| [i as f64, (i % 10) as f64]).collect();
+    /// let tree = RTree::bulk_load_with_algorithm(points, BulkLoadAlgorithm::Str);
+    /// assert_eq!(tree.size(), 100);
+    /// assert!(tree.contains(&[42.0, 2.0]));
+    /// ```
+    pub fn bulk_load_with_algorithm(elements: Vec<T>, algorithm: BulkLoadAlgorithm) -> Self {
+        Self::bulk_load_with_params_and_algorithm(elements, algorithm)
+    }
+
+    /// Creates a new r-tree with some elements already inserted, using the priority r-tree
+    /// (PR-tree) bulk loading algorithm.
+    ///
+    /// Unlike other bulk loading algorithms, the PR-tree guarantees that a window query visits
+    /// `O((n/B)^(1 - 1/d) + k/B)` nodes, where `B` is the node size, `d` the dimension and `k`
+    /// the number of reported elements. This bound holds for any input, making the PR-tree a
+    /// good choice for rectangles with extreme sizes and aspect ratios. For uniformly
+    /// distributed data, [RTree::bulk_load] usually yields slightly faster queries.
+    ///
+    /// This algorithm is only available for elements whose envelope is an [AABB].
+    ///
+    /// See [Arge, Lars, et al. "The priority R-tree: A practically efficient and worst-case optimal R-tree." ACM Transactions on Algorithms 4.1 (2008): 1-30.](https://doi.org/10.1145/1328911.1328920)
+    ///
+    /// # Example
+    /// ```
+    /// use rstar::primitives::Rectangle;
+    /// use rstar::{RTree, AABB};
+    ///
+    /// let rectangles: Vec<_> = (0..100)
+    ///     .map(|i| Rectangle::from_corners([i as f64, 0.0], [i as f64 + 0.5, 100.0]))
+    ///     .collect();
+    /// let tree = RTree::bulk_load_pr_tree(rectangles);
+    /// let window = AABB::from_corners([10.0, 10.0], [12.0, 12.0]);
+    /// assert_eq!(tree.locate_in_envelope_intersecting(&window).count(), 3);
+    /// ```
+    pub fn bulk_load_pr_tree<P>(elements: Vec<T>) -> Self
+    where
+        P: Point,
+        T: RTreeObject<Envelope = AABB<P>>,
+    {
+        Self::bulk_load_pr_tree_with_params(elements)
+    }
+
+    /// Creates a new r-tree with some elements already inserted, using the surface area
+    /// heuristic (SAH).
+    ///
+    /// The tree is built top down. Elements are recursively split to minimize the surface area
+    /// of the resulting nodes, weighted by their number of elements. This minimizes the expected
+    /// cost of ray casts and collision queries, making the tree suitable as a bounding volume
+    /// hierarchy. The split positions are chosen among a fixed number of bins per axis
+    /// (_binned SAH_).
+    ///
+    /// This algorithm is only available for elements whose envelope is an [AABB].
+    ///
+    /// See [Wald, Ingo. "On fast construction of SAH-based bounding volume hierarchies." 2007 IEEE Symposium on Interactive Ray Tracing. IEEE, 2007.](https://doi.org/10.1109/RT.2007.4342588)
+    ///
+    /// # Example
+    /// ```
+    /// use rstar::primitives::Rectangle;
+    /// use rstar::RTree;
+    ///
+    /// let triangles_bounds: Vec<_> = (0..100)
+    ///     .map(|i| Rectangle::from_corners([i as f64, 0.0, 0.0], [i as f64 + 1.0, 1.0, 1.0]))
+    ///     .collect();
+    /// let tree = RTree::bulk_load_sah(triangles_bounds);
+    /// assert_eq!(tree.size(), 100);
+    /// ```
+    pub fn bulk_load_sah<P>(elements: Vec<T>) -> Self
+    where
+        P: Point,
+        T: RTreeObject<Envelope = AABB<P>>,
+    {
+        Self::bulk_load_sah_with_params(elements)
+    }
+
+    /// Creates a new, empty r-tree using runtime parameters.
+    ///
+    /// Refer to [RTreeConfig] for more information and a usage example.
+    ///
+    /// # Panics
+    /// Panics if the configuration is invalid, e.g. if `min_size` is larger than half of
+    /// `max_size`. The same restrictions as for [RTreeParams] apply.
+    pub fn with_config(config: RTreeConfig) -> Self {
+        Self::new_with_config(config)
+    }
+
+    /// Creates a new r-tree with some given elements using runtime parameters.
+    ///
+    /// For more information refer to [RTree::bulk_load] and [RTreeConfig].
+    ///
+    /// # Panics
+    /// Panics if the configuration is invalid, see [RTree::with_config].
+    pub fn bulk_load_with_config(elements: Vec<T>, config: RTreeConfig) -> Self {
+        Self::new_from_bulk_loading(elements, config, bulk_load::bulk_load_sequential)
+    }
+
+    /// Creates a new r-tree with some elements already inserted, filling its nodes to the given
+    /// percentage.
+    ///
+    /// See [RTree::bulk_load] and [RTreeParams::BULK_LOAD_FILL_FACTOR] for more information.
+    ///
+    /// # Example
+    /// ```
+    /// use rstar::RTree;
+    ///
+    /// let points: Vec<_> = (0..100).map(|i| [i as f64, (i % 10) as f64]).collect();
+    /// // Leave room for subsequent insertions
+    /// let mut tree = RTree::bulk_load_with_fill_factor(points, 70);
+    /// tree.insert([0.5, 0.5]);
+    /// assert_eq!(tree.size(), 101);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `fill_factor` is `0` or larger than `100`.
+    pub fn bulk_load_with_fill_factor(elements: Vec<T>, fill_factor: u8) -> Self {
+        let config = RTreeConfig {
+            bulk_load_fill_factor: fill_factor,
+            ..RTreeConfig::default()
+        };
+        Self::bulk_load_with_config(elements, config)
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T> RTree<T>
+where
+    T: RTreeObject + Send,
+    T::Envelope: Send,
+{
+    /// Creates a new r-tree with some elements already inserted, using multiple threads.
+    ///
+    /// Yields the same tree as [RTree::bulk_load]. Elements are partitioned and independent
+    /// subtrees are created concurrently on [rayon's](https://crates.io/crates/rayon) global
+    /// thread pool.
+    ///
+    /// Requires the `rayon` feature.
+    ///
+    /// # Example
+    /// ```
+    /// use rstar::RTree;
+    ///
+    /// let points: Vec<_> = (0..1000).map(|i| [i as f64, (i % 10) as f64]).collect();
+    /// let tree = RTree::bulk_load_parallel(points);
+    /// assert_eq!(tree.size(), 1000);
+    /// assert!(tree.contains(&[42.0, 2.0]));
+    /// ```
+    pub fn bulk_load_parallel(elements: Vec<T>) -> Self {
+        Self::bulk_load_parallel_with_params(elements)
+    }
+
+    /// Creates a new r-tree with some given elements using runtime parameters and multiple
+    /// threads.
+    ///
+    /// For more information refer to [RTree::bulk_load_parallel] and [RTreeConfig].
+    ///
+    /// # Panics
+    /// Panics if the configuration is invalid, see [RTree::with_config].
+    pub fn bulk_load_parallel_with_config(elements: Vec<T>, config: RTreeConfig) -> Self {
+        Self::new_from_bulk_loading(elements, config, bulk_load::bulk_load_parallel)
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T, Params> RTree<T, Params>
+where
+    Params: RTreeParams,
+    T: RTreeObject + Send,
+    T::Envelope: Send,
+{
+    /// Creates a new r-tree with some given elements and configurable parameters, using
+    /// multiple threads.
+    ///
+    /// For more information refer to [RTree::bulk_load_parallel] and [RTreeParams].
+    pub fn bulk_load_parallel_with_params(elements: Vec<T>) -> Self {
+        Self::new_from_bulk_loading(
+            elements,
+            RTreeConfig::from_params::<Params>(),
+            bulk_load::bulk_load_parallel,
+        )
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T, Params> RTree<T, Params>
+where
+    Params: RTreeParams,
+    T: RTreeObject + Sync,
+    T::Envelope: Send + Sync,
+{
+    /// Returns a parallel iterator over all elements contained in the tree.
+    ///
+    /// Independent subtrees are traversed concurrently on [rayon's](https://crates.io/crates/rayon)
+    /// global thread pool. The order in which the elements are returned is not specified.
+    ///
+    /// Requires the `rayon` feature.
+    ///
+    /// # Example
+    /// ```
+    /// use rayon::prelude::*;
+    /// use rstar::RTree;
+    ///
+    /// let points: Vec<_> = (0..1000).map(|i| [i as f64, (i % 10) as f64]).collect();
+    /// let tree = RTree::bulk_load(points);
+    /// let sum: f64 = tree.par_iter().map(|point| point[1]).sum();
+    /// assert_eq!(sum, 4500.0);
+    /// ```
+    pub fn par_iter(&self) -> impl ParallelIterator<Item = &T> {
+        ParallelSelectionIterator::new(&self.root, SelectAllFunc)
+    }
+
+    /// Parallel variant of [RTree::locate_in_envelope].
+    ///
+    /// Requires the `rayon` feature.
+    pub fn par_locate_in_envelope(
+        &self,
+        envelope: &T::Envelope,
+    ) -> impl ParallelIterator<Item = &T> {
+        ParallelSelectionIterator::new(&self.root, SelectInEnvelopeFunction::new(*envelope))
+    }
+
+    /// Parallel variant of [RTree::locate_in_envelope_intersecting].
+    ///
+    /// Requires the `rayon` feature.
+    pub fn par_locate_in_envelope_intersecting(
+        &self,
+        envelope: &T::Envelope,
+    ) -> impl ParallelIterator<Item = &T> {
+        ParallelSelectionIterator::new(&self.root, SelectInEnvelopeFuncIntersecting::new(*envelope))
+    }
+
+    /// Parallel variant of [RTree::locate_with_selection_function].
+    ///
+    /// The selection function is shared between all threads.
+    ///
+    /// Requires the `rayon` feature.
+    pub fn par_locate_with_selection_function<S>(
+        &self,
+        selection_function: S,
+    ) -> impl ParallelIterator<Item = &T>
+    where
+        S: SelectionFunction<T> + Send + Sync,
+    {
+        ParallelSelectionIterator::new(&self.root, selection_function)
+    }
+
+    /// Parallel variant of [RTree::locate_within_distance].
+    ///
+    /// Requires the `rayon` feature.
+    pub fn par_locate_within_distance(
+        &self,
+        query_point: <T::Envelope as Envelope>::Point,
+        max_squared_radius: <<T::Envelope as Envelope>::Point as Point>::Scalar,
+    ) -> impl ParallelIterator<Item = &T>
+    where
+        T: PointDistance,
+        <T::Envelope as Envelope>::Point: Send + Sync,
+        <<T::Envelope as Envelope>::Point as Point>::Scalar: Send + Sync,
+    {
+        let selection_function = SelectWithinDistanceFunction::new(query_point, max_squared_radius);
+        ParallelSelectionIterator::new(&self.root, selection_function)
+    }
+
+    /// Parallel variant of [RTree::intersection_candidates_with_other_tree].
+    ///
+    /// Independent pairs of nodes of both trees are distributed across rayon's work-stealing
+    /// thread pool. The order in which the pairs are returned is not specified.
+    ///
+    /// Requires the `rayon` feature.
+    ///
+    /// # Example
+    /// ```
+    /// use rayon::prelude::*;
+    /// use rstar::primitives::Rectangle;
+    /// use rstar::RTree;
+    ///
+    /// let buildings = RTree::bulk_load(vec![
+    ///     Rectangle::from_corners([0.0, 0.0], [1.0, 1.0]),
+    ///     Rectangle::from_corners([5.0, 5.0], [6.0, 6.0]),
+    /// ]);
+    /// let zones = RTree::bulk_load(vec![Rectangle::from_corners([0.5, 0.5], [2.0, 2.0])]);
+    /// let affected = buildings
+    ///     .par_intersection_candidates_with_other_tree(&zones)
+    ///     .count();
+    /// assert_eq!(affected, 1);
+    /// ```
+    pub fn par_intersection_candidates_with_other_tree<'a, U>(
+        &'a self,
+        other: &'a RTree<U>,
+    ) -> impl ParallelIterator<Item = (&'a T, &'a U)>
+    where
+        U: RTreeObject<Envelope = T::Envelope> + Sync,
+    {
+        ParallelIntersectionIterator::new(self.root(), other.root())
+    }
+
+    /// Parallel variant of [RTree::nearest_neighbors_batch].
+    ///
+    /// The sorted query points are split into chunks of consecutive queries which are answered
+    /// concurrently. Returns the same result as [RTree::nearest_neighbors_batch].
+    ///
+    /// Requires the `rayon` feature.
+    pub fn par_nearest_neighbors_batch(
+        &self,
+        query_points: &[<T::Envelope as Envelope>::Point],
+        k: usize,
+    ) -> Vec<Vec<&T>>
+    where
+        T: PointDistance,
+        <T::Envelope as Envelope>::Point: Sync,
+    {
+        nearest_neighbor_batch::par_nearest_neighbors_batch(&self.root, query_points, k)
+    }
 }
 
 impl<T, Params> RTree<T, Params>
@@ -218,10 +568,15 @@ where
     /// The tree's compile time parameters must be specified. Refer to the
     /// [RTreeParams] trait for more information and a usage example.
     pub fn new_with_params() -> Self {
-        verify_parameters::<T, Params>();
+        Self::new_with_config(RTreeConfig::from_params::<Params>())
+    }
+
+    fn new_with_config(config: RTreeConfig) -> Self {
+        verify_config::<T>(&config);
         RTree {
-            root: ParentNode::new_root::<Params>(),
+            root: ParentNode::new_root(&config),
             size: 0,
+            config,
             _params: Default::default(),
         }
     }
@@ -231,7 +586,58 @@ where
     /// For more information refer to [RTree::bulk_load]
     /// and [RTreeParams].
     pub fn bulk_load_with_params(elements: Vec<T>) -> Self {
-        Self::new_from_bulk_loading(elements, bulk_load::bulk_load_sequential::<_, Params>)
+        Self::new_from_bulk_loading(
+            elements,
+            RTreeConfig::from_params::<Params>(),
+            bulk_load::bulk_load_sequential,
+        )
+    }
+
+    /// Creates a new r-tree with some given elements and configurable parameters, using the
+    /// priority r-tree bulk loading algorithm.
+    ///
+    /// For more information refer to [RTree::bulk_load_pr_tree] and [RTreeParams].
+    pub fn bulk_load_pr_tree_with_params<P>(elements: Vec<T>) -> Self
+    where
+        P: Point,
+        T: RTreeObject<Envelope = AABB<P>>,
+    {
+        Self::new_from_bulk_loading(
+            elements,
+            RTreeConfig::from_params::<Params>(),
+            bulk_load::bulk_load_pr_tree,
+        )
+    }
+
+    /// Creates a new r-tree with some given elements and configurable parameters, using the
+    /// surface area heuristic.
+    ///
+    /// For more information refer to [RTree::bulk_load_sah] and [RTreeParams].
+    pub fn bulk_load_sah_with_params<P>(elements: Vec<T>) -> Self
+    where
+        P: Point,
+        T: RTreeObject<Envelope = AABB<P>>,
+    {
+        Self::new_from_bulk_loading(
+            elements,
+            RTreeConfig::from_params::<Params>(),
+            bulk_load::bulk_load_sah,
+        )
+    }
+
+    /// Creates a new r-tree with some given elements, configurable parameters and a specific
+    /// bulk loading algorithm.
+    ///
+    /// For more information refer to [RTree::bulk_load_with_algorithm] and [RTreeParams].
+    pub fn bulk_load_with_params_and_algorithm(
+        elements: Vec<T>,
+        algorithm: BulkLoadAlgorithm,
+    ) -> Self {
+        Self::new_from_bulk_loading(
+            elements,
+            RTreeConfig::from_params::<Params>(),
+            algorithm.bulk_load(),
+        )
     }
 
     /// Returns the number of objects in an r-tree.
@@ -275,12 +681,61 @@ where
     /// method is primarily meant for own implementations of [RTreeObject]
     /// which can contain arbitrary additional data.
     /// If the position or location of an inserted object need to change, you will need to [RTree::remove]
-    /// and reinsert it.
+    /// and reinsert it, or call [RTree::refit_envelopes] once all changes have been made.
     ///
     pub fn iter_mut(&mut self) -> RTreeIteratorMut<T> {
         RTreeIteratorMut::new(&mut self.root, SelectAllFunc)
     }
 
+    /// Removes all elements from the tree and returns them as an iterator.
+    ///
+    /// The tree is empty afterwards. The order in which the elements are returned is not
+    /// specified.
+    ///
+    /// # Example
+    /// ```
+    /// use rstar::RTree;
+    ///
+    /// let mut tree = RTree::bulk_load(vec![[0.0, 0.1], [0.3, 0.2], [0.4, 0.2]]);
+    /// let points: Vec<_> = tree.drain().collect();
+    /// assert_eq!(points.len(), 3);
+    /// assert_eq!(tree.size(), 0);
+    /// ```
+    pub fn drain(&mut self) -> RTreeIntoIterator<T> {
+        let size = ::std::mem::replace(&mut self.size, 0);
+        let root = ::std::mem::replace(&mut self.root, ParentNode::new_root(&self.config));
+        RTreeIntoIterator::new(root, size)
+    }
+
+    /// Recalculates the envelopes of all internal nodes.
+    ///
+    /// Changing an element's envelope through a mutable reference (e.g. by using
+    /// [RTree::iter_mut] or [RTree::locate_in_envelope_mut]) leaves the envelopes of its parent
+    /// nodes outdated and subsequent queries may miss elements. Calling this method afterwards
+    /// restores a valid tree.
+    ///
+    /// Only envelopes are updated, the tree's structure is not changed. If many elements have
+    /// moved far from their original position, query performance may degrade. In this case,
+    /// rebuilding the tree with [RTree::bulk_load] will yield better results.
+    ///
+    /// # Runtime
+    /// This method runs in `O(n)`.
+    ///
+    /// # Example
+    /// ```
+    /// use rstar::RTree;
+    ///
+    /// let mut tree = RTree::bulk_load(vec![[0.0, 0.0], [1.0, 1.0]]);
+    /// for point in tree.iter_mut() {
+    ///     point[0] += 10.0;
+    /// }
+    /// tree.refit_envelopes();
+    /// assert_eq!(tree.nearest_neighbor(&[11.0, 1.0]), Some(&[11.0, 1.0]));
+    /// ```
+    pub fn refit_envelopes(&mut self) {
+        self.root.refit_envelopes();
+    }
+
     /// Returns all elements contained in an [Envelope].
     ///
     /// Usually, an envelope is an [axis aligned bounding box](crate::AABB). This
@@ -306,6 +761,8 @@ where
     }
 
     /// Mutable variant of [locate_in_envelope](#method.locate_in_envelope).
+    ///
+    /// See [RTree::refit_envelopes] if the envelopes of the returned elements are changed.
     pub fn locate_in_envelope_mut(&mut self, envelope: &T::Envelope) -> LocateInEnvelopeMut<T> {
         LocateInEnvelopeMut::new(&mut self.root, SelectInEnvelopeFunction::new(*envelope))
     }
@@ -410,24 +867,64 @@ where
         &self.root
     }
 
-    pub(crate) fn root_mut(&mut self) -> &mut ParentNode<T> {
+    /// Returns the tree's root node for modification.
+    ///
+    /// This method is meant for implementing custom [insertion strategies](InsertionStrategy)
+    /// which may rearrange the tree's nodes as they see fit. The number of elements contained
+    /// in the tree must not be changed, and all leaves must remain at the same depth. Use
+    /// [RTree::check_invariants] to verify the tree's structure after modifying it.
+    pub fn root_mut(&mut self) -> &mut ParentNode<T> {
         &mut self.root
     }
 
+    /// Returns the tree's parameters.
+    ///
+    /// For trees created with compile time parameters, the values of [RTreeParams] are returned.
+    pub fn config(&self) -> &RTreeConfig {
+        &self.config
+    }
+
+    /// Checks the internal structure of the tree.
+    ///
+    /// This is mainly useful for testing custom [insertion strategies](InsertionStrategy). If the
+    /// `debug` feature is enabled, this check is performed after every call to [RTree::insert].
+    ///
+    /// Node sizes are not checked as bulk loading, removal and some insertion strategies may
+    /// create nodes with less than `MIN_SIZE` or more than `MAX_SIZE` children.
+    ///
+    /// # Panics
+    /// Panics if the envelope of any node differs from the envelope of its children, if the
+    /// tree's leaves are located at different depths, if a parent node other than the root is
+    /// empty or if the number of leaves differs from [RTree::size].
+    pub fn check_invariants(&self) {
+        let leaf_count = self.root.check_invariants();
+        assert_eq!(
+            leaf_count, self.size,
+            "The tree's size differs from its number of leaves"
+        );
+    }
+
     fn new_from_bulk_loading(
         elements: Vec<T>,
-        root_loader: impl Fn(Vec<T>) -> ParentNode<T>,
+        config: RTreeConfig,
+        bulk_load: fn(Vec<T>, &RTreeConfig) -> ParentNode<T>,
     ) -> Self {
-        verify_parameters::<T, Params>();
+        verify_config::<T>(&config);
         let size = elements.len();
         let root = if size == 0 {
-            ParentNode::new_root::<Params>()
+            ParentNode::new_root(&config)
         } else {
-            root_loader(elements)
+            bulk_load(elements, &config.bulk_load_config())
         };
+        Self::new_from_root(root, size, config)
+    }
+
+    /// Creates a tree from a root node that was built by a bulk loading algorithm.
+    pub(crate) fn new_from_root(root: ParentNode<T>, size: usize, config: RTreeConfig) -> Self {
         RTree {
             root,
             size,
+            config,
             _params: Default::default(),
         }
     }
@@ -465,6 +962,8 @@ where
     }
 
     /// Mutable variant of [RTree::locate_at_point].
+    ///
+    /// See [RTree::refit_envelopes] if the envelope of the returned element is changed.
     pub fn locate_at_point_mut(
         &mut self,
         point: &<T::Envelope as Envelope>::Point,
@@ -634,6 +1133,37 @@ where
         nearest_neighbor::nearest_neighbors(&self.root, *query_point)
     }
 
+    /// Returns the `k` nearest neighbors of each of the given query points.
+    ///
+    /// The result contains one `Vec` per query point, in the order of `query_points`. Each
+    /// `Vec` is sorted by distance and contains less than `k` elements only if the tree does.
+    ///
+    /// The queries are answered in the order of their Hilbert values, which places nearby
+    /// query points next to each other. The neighbors found for one query then bound the
+    /// search radius of the next query, which prunes most of the tree early. For large and
+    /// spatially coherent sets of query points, this is considerably faster than querying each
+    /// point with [RTree::nearest_neighbor_iter].
+    ///
+    /// # Example
+    /// ```
+    /// use rstar::RTree;
+    /// let tree = RTree::bulk_load(vec![
+    ///   [0.0, 0.0],
+    ///   [0.0, 1.0],
+    ///   [1.0, 0.0],
+    /// ]);
+    /// let neighbors = tree.nearest_neighbors_batch(&[[0.1, 0.9], [1.0, 0.1]], 2);
+    /// assert_eq!(neighbors[0], vec![&[0.0, 1.0], &[0.0, 0.0]]);
+    /// assert_eq!(neighbors[1], vec![&[1.0, 0.0], &[0.0, 0.0]]);
+    /// ```
+    pub fn nearest_neighbors_batch(
+        &self,
+        query_points: &[<T::Envelope as Envelope>::Point],
+        k: usize,
+    ) -> Vec<Vec<&T>> {
+        nearest_neighbor_batch::nearest_neighbors_batch(&self.root, query_points, k)
+    }
+
     /// Returns all elements of the tree within a certain distance.
     ///
     /// The elements may be returned in any order. Each returned element
@@ -738,13 +1268,187 @@ where
     ///
     /// If the element is already present in the tree, it will now be present twice.
     ///
+    /// The element is inserted according to the [strategy](RTreeConfig::strategy) of the tree's
+    /// configuration, which defaults to [RTreeParams::DefaultInsertionStrategy].
+    ///
     /// # Runtime
     /// This method runs in `O(log(n))`.
     /// The [r-tree documentation](RTree) contains more information about
     /// r-tree performance.
     pub fn insert(&mut self, t: T) {
-        Params::DefaultInsertionStrategy::insert(self, t);
+        match self.config.strategy {
+            InsertionStrategyKind::Default => Params::DefaultInsertionStrategy::insert(self, t),
+            InsertionStrategyKind::RStar => RStarInsertionStrategy::insert(self, t),
+            InsertionStrategyKind::RRStar => RRStarInsertionStrategy::insert(self, t),
+            InsertionStrategyKind::GuttmanLinear => GuttmanLinearInsertionStrategy::insert(self, t),
+            InsertionStrategyKind::GuttmanQuadratic => {
+                GuttmanQuadraticInsertionStrategy::insert(self, t)
+            }
+            InsertionStrategyKind::Hilbert => HilbertInsertionStrategy::insert(self, t),
+            InsertionStrategyKind::XTree => XTreeInsertionStrategy::insert(self, t),
+        }
         self.size += 1;
+        #[cfg(feature = "debug")]
+        self.check_invariants();
+    }
+
+    /// Inserts many elements into the r-tree at once.
+    ///
+    /// The elements are first [bulk loaded](RTree::bulk_load) into a separate tree whose
+    /// subtrees are then attached to this tree at the matching height. This is considerably
+    /// faster than calling [RTree::insert] for each element, especially if the number of new
+    /// elements is large. Only the attached subtrees are inserted according to the r-star
+    /// heuristic, [RTreeParams::DefaultInsertionStrategy] is not used.
+    ///
+    /// # Runtime
+    /// This method runs in `O(m * log(m) + s * log(n + m))`, where `m` is the number of inserted
+    /// elements, `n` the tree's size and `s` the number of subtrees and elements that are
+    /// attached one by one. Subtrees with less than `MIN_SIZE` children and subtrees as high
+    /// as the tree are dissolved and their children are attached individually. `s` is usually
+    /// small compared to `m`, but is only bounded by `O(min(n, m))`.
+    ///
+    /// # Example
+    /// ```
+    /// use rstar::RTree;
+    ///
+    /// let mut tree = RTree::bulk_load(vec![[0.0, 0.0], [1.0, 1.0]]);
+    /// tree.insert_bulk(vec![[2.0, 2.0], [3.0, 3.0], [4.0, 4.0]]);
+    /// assert_eq!(tree.size(), 5);
+    /// assert!(tree.contains(&[3.0, 3.0]));
+    /// ```
+    pub fn insert_bulk(&mut self, elements: Vec<T>) {
+        self.insert_bulk_with_fill_factor(elements, self.config.bulk_load_fill_factor);
+    }
+
+    /// Inserts several elements into the tree, filling the attached subtrees' nodes to the
+    /// given percentage.
+    ///
+    /// [RTree::insert_bulk] uses the fill factor of the tree's [configuration](RTree::config).
+    /// See [RTreeParams::BULK_LOAD_FILL_FACTOR] for more information.
+    ///
+    /// # Panics
+    /// Panics if `fill_factor` is `0` or larger than `100`.
+    pub fn insert_bulk_with_fill_factor(&mut self, elements: Vec<T>, fill_factor: u8) {
+        let config = RTreeConfig {
+            bulk_load_fill_factor: fill_factor,
+            ..self.config
+        };
+        verify_config::<T>(&config);
+        if elements.is_empty() {
+            return;
+        }
+        let size = elements.len();
+        let subtree = bulk_load::bulk_load_sequential(elements, &config.bulk_load_config());
+        self.graft(subtree, size);
+    }
+
+    /// Removes all elements contained in an envelope and returns them as a new r-tree.
+    ///
+    /// Subtrees that lie completely within the envelope are moved into the new tree as a
+    /// whole, only the nodes along the envelope's border need to be unpacked.
+    /// See also [RTree::split_off_envelope_intersecting].
+    ///
+    /// # Example
+    /// ```
+    /// use rstar::{RTree, AABB};
+    ///
+    /// let mut tree = RTree::bulk_load(vec![[0.0, 0.0], [1.0, 1.0], [2.0, 2.0]]);
+    /// let left = tree.split_off_envelope(&AABB::from_corners([0.0, 0.0], [1.0, 1.0]));
+    /// assert_eq!(left.size(), 2);
+    /// assert_eq!(tree.size(), 1);
+    /// assert!(tree.contains(&[2.0, 2.0]));
+    /// ```
+    pub fn split_off_envelope(&mut self, envelope: &T::Envelope) -> Self {
+        self.split_off_with_selection_function(envelope, SelectInEnvelopeFunction::new(*envelope))
+    }
+
+    /// Removes all elements intersecting an envelope and returns them as a new r-tree.
+    ///
+    /// See [RTree::split_off_envelope] and [RTree::locate_in_envelope_intersecting] for more
+    /// information.
+    pub fn split_off_envelope_intersecting(&mut self, envelope: &T::Envelope) -> Self {
+        self.split_off_with_selection_function(
+            envelope,
+            SelectInEnvelopeFuncIntersecting::new(*envelope),
+        )
+    }
+
+    fn split_off_with_selection_function<F>(&mut self, envelope: &T::Envelope, function: F) -> Self
+    where
+        F: SelectionFunction<T>,
+    {
+        let mut subtrees = Vec::new();
+        let mut leaves = Vec::new();
+        removal::split_off(
+            &mut self.root,
+            envelope,
+            &function,
+            &mut subtrees,
+            &mut leaves,
+        );
+        let mut result = Self::new_with_config(self.config);
+        result.insert_bulk(leaves);
+        for subtree in subtrees {
+            let size = RTreeIterator::new(&subtree, SelectAllFunc).count();
+            result.graft(subtree, size);
+        }
+        self.size -= result.size;
+        result
+    }
+
+    /// Merges two r-trees into one.
+    ///
+    /// The nodes of the lower tree are attached to the higher tree at the matching height,
+    /// existing subtrees are reused and only the nodes along the insertion paths are modified.
+    /// This is considerably faster than inserting the elements of one tree into the other.
+    /// The merged tree uses the configuration of `self`, see [RTree::append].
+    ///
+    /// # Example
+    /// ```
+    /// use rstar::RTree;
+    ///
+    /// let left = RTree::bulk_load(vec![[0.0, 0.0], [1.0, 1.0]]);
+    /// let right = RTree::bulk_load(vec![[2.0, 2.0], [3.0, 3.0]]);
+    /// let merged = left.merge(right);
+    /// assert_eq!(merged.size(), 4);
+    /// assert!(merged.contains(&[3.0, 3.0]));
+    /// ```
+    pub fn merge(mut self, mut other: Self) -> Self {
+        self.append(&mut other);
+        self
+    }
+
+    /// Moves all elements of `other` into `self`, leaving `other` empty.
+    ///
+    /// The resulting tree keeps the [configuration](RTree::config) of `self`. If the nodes of
+    /// `other` may be smaller or larger than this configuration allows, its elements are
+    /// [bulk inserted](RTree::insert_bulk) instead of reusing its subtrees.
+    ///
+    /// See [RTree::merge] for more information.
+    pub fn append(&mut self, other: &mut Self) {
+        let compatible = self.config.min_size <= other.config.min_size
+            && other.config.max_size <= self.config.max_size;
+        if !compatible {
+            let elements = other.drain().collect();
+            self.insert_bulk(elements);
+            return;
+        }
+        let other_size = ::std::mem::replace(&mut other.size, 0);
+        let other_root = ::std::mem::replace(&mut other.root, ParentNode::new_root(&other.config));
+        self.graft(other_root, other_size);
+    }
+
+    fn graft(&mut self, subtree: ParentNode<T>, subtree_size: usize) {
+        let root_height = graft::height(&self.root);
+        let subtree_height = graft::height(&subtree);
+        graft::graft(
+            &mut self.root,
+            root_height,
+            subtree,
+            subtree_height,
+            &self.config,
+        );
+        self.size += subtree_size;
     }
 }
 
@@ -756,6 +1460,19 @@ where
 {
 }
 
+impl<T, Params> IntoIterator for RTree<T, Params>
+where
+    T: RTreeObject,
+    Params: RTreeParams,
+{
+    type IntoIter = RTreeIntoIterator<T>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        RTreeIntoIterator::new(self.root, self.size)
+    }
+}
+
 impl<'a, T, Params> IntoIterator for &'a RTree<T, Params>
 where
     T: RTreeObject,
@@ -782,12 +1499,35 @@ where
     }
 }
 
+impl<T, Params> ::std::iter::FromIterator<T> for RTree<T, Params>
+where
+    T: RTreeObject,
+    Params: RTreeParams,
+{
+    /// Creates an r-tree by [bulk loading](RTree::bulk_load) all elements of an iterator.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::bulk_load_with_params(iter.into_iter().collect())
+    }
+}
+
+impl<T, Params> Extend<T> for RTree<T, Params>
+where
+    T: RTreeObject,
+    Params: RTreeParams,
+{
+    /// Inserts all elements of an iterator, see [RTree::insert_bulk].
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.insert_bulk(iter.into_iter().collect())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::RTree;
     use crate::algorithm::rstar::RStarInsertionStrategy;
+    use crate::node::{ParentNode, RTreeNode};
     use crate::params::RTreeParams;
-    use crate::test_utilities::{create_random_points, SEED_1};
+    use crate::test_utilities::{create_random_points, create_random_rectangles, SEED_1, SEED_2};
     use crate::DefaultParams;
 
     struct TestParams;
@@ -828,6 +1568,394 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_refit_envelopes() {
+        let points = create_random_points(1000, SEED_1);
+        let mut tree = RTree::new();
+        for point in &points {
+            tree.insert(*point);
+        }
+        for point in tree.iter_mut() {
+            point[0] = point[0] * 2.0 + 5.0;
+        }
+        tree.refit_envelopes();
+        tree.root().sanity_check::<DefaultParams>(false);
+        for point in &points {
+            let moved = [point[0] * 2.0 + 5.0, point[1]];
+            assert!(tree.contains(&moved));
+            assert_eq!(tree.nearest_neighbor(&moved), Some(&moved));
+        }
+    }
+
+    #[test]
+    fn test_with_config() {
+        use crate::{InsertionStrategyKind::*, RTreeConfig};
+        let points = create_random_points(500, SEED_1);
+        for &strategy in &[
+            Default,
+            RStar,
+            RRStar,
+            GuttmanLinear,
+            GuttmanQuadratic,
+            Hilbert,
+            XTree,
+        ] {
+            let config = RTreeConfig {
+                min_size: 5,
+                max_size: 12,
+                reinsertion_count: 3,
+                strategy,
+                ..RTreeConfig::default()
+            };
+            let mut tree = RTree::with_config(config);
+            for point in &points {
+                tree.insert(*point);
+            }
+            tree.check_invariants();
+            assert_eq!(tree.config(), &config);
+            assert!(tree
+                .root()
+                .sanity_check_with_sizes(Some(config.min_size), Some(config.max_size))
+                .is_some());
+            for point in &points {
+                assert!(tree.contains(point));
+            }
+        }
+    }
+
+    #[test]
+    fn test_bulk_load_with_config() {
+        use crate::RTreeConfig;
+        let points = create_random_points(1000, SEED_1);
+        let small_nodes = RTreeConfig {
+            min_size: 2,
+            max_size: 4,
+            reinsertion_count: 1,
+            ..Default::default()
+        };
+        let large_nodes = RTreeConfig {
+            min_size: 10,
+            max_size: 30,
+            ..Default::default()
+        };
+        let mut small_tree = RTree::bulk_load_with_config(points.clone(), small_nodes);
+        let large_tree = RTree::bulk_load_with_config(points.clone(), large_nodes);
+        let small_height = small_tree.root().sanity_check_unsized().unwrap();
+        let large_height = large_tree.root().sanity_check_unsized().unwrap();
+        assert!(small_height > large_height);
+
+        // Derived trees keep the configuration
+        let split_off =
+            small_tree.split_off_envelope(&crate::AABB::from_corners([0.0, 0.0], [0.5, 0.5]));
+        assert_eq!(split_off.config(), &small_nodes);
+        assert_eq!(split_off.size() + small_tree.size(), 1000);
+    }
+
+    #[test]
+    fn test_bulk_load_with_fill_factor() {
+        use crate::{BulkLoadAlgorithm, RTreeConfig};
+        let points = create_random_points(1000, SEED_1);
+        let config = RTreeConfig {
+            min_size: 4,
+            max_size: 12,
+            bulk_load_fill_factor: 70,
+            ..Default::default()
+        };
+        assert_eq!(config.bulk_load_node_size(), 9);
+        let mut tree = RTree::bulk_load_with_config(points.clone(), config);
+        tree.check_invariants();
+        assert_eq!(tree.config(), &config);
+        assert!(tree.root().sanity_check_with_sizes(None, Some(9)).is_some());
+        for point in create_random_points(100, SEED_2) {
+            tree.insert(point);
+        }
+        tree.check_invariants();
+        assert_eq!(tree.size(), 1100);
+
+        // The fill factor applies to all bulk loading algorithms
+        let tree: RTree<_, FillFactorParams> =
+            RTree::bulk_load_with_params_and_algorithm(points, BulkLoadAlgorithm::Hilbert);
+        tree.check_invariants();
+        assert!(tree.root().sanity_check_with_sizes(None, Some(3)).is_some());
+    }
+
+    #[test]
+    fn test_bulk_insertion_with_fill_factor() {
+        use crate::RTreeConfig;
+        fn count_nodes<T: crate::RTreeObject>(node: &ParentNode<T>) -> usize {
+            1 + node
+                .children
+                .iter()
+                .map(|child| match child {
+                    RTreeNode::Parent(ref data) => count_nodes(data),
+                    RTreeNode::Leaf(_) => 0,
+                })
+                .sum::<usize>()
+        }
+
+        let points = create_random_points(1000, SEED_1);
+        let envelope = crate::AABB::from_corners([0.0, 0.0], [0.5, 0.5]);
+        let full = RTreeConfig {
+            min_size: 4,
+            max_size: 12,
+            ..Default::default()
+        };
+        let half = RTreeConfig {
+            bulk_load_fill_factor: 50,
+            ..full
+        };
+        // All bulk insertions use the tree's fill factor
+        let mut trees = Vec::new();
+        for &config in &[full, half] {
+            let mut tree = RTree::bulk_load_with_config(points[..100].to_vec(), config);
+            tree.insert_bulk(points[100..500].to_vec());
+            tree.extend(points[500..].iter().copied());
+            tree.check_invariants();
+            let split_off = tree.split_off_envelope(&envelope);
+            split_off.check_invariants();
+            trees.push((tree, split_off));
+        }
+        let (full_tree, full_split_off) = &trees[0];
+        let (half_tree, half_split_off) = &trees[1];
+        assert!(count_nodes(half_tree.root()) > count_nodes(full_tree.root()) * 3 / 2);
+        assert!(count_nodes(half_split_off.root()) > count_nodes(full_split_off.root()) * 3 / 2);
+
+        let mut quarter_tree = RTree::with_config(full);
+        quarter_tree.insert_bulk_with_fill_factor(points.clone(), 25);
+        quarter_tree.check_invariants();
+        assert_eq!(quarter_tree.config(), &full);
+        let mut full_tree = RTree::with_config(full);
+        full_tree.insert_bulk(points.clone());
+        assert!(count_nodes(quarter_tree.root()) > count_nodes(full_tree.root()) * 2);
+
+        let tree = RTree::bulk_load_with_fill_factor(points.clone(), 50);
+        assert_eq!(tree.config().bulk_load_fill_factor, 50);
+        assert!(count_nodes(tree.root()) > count_nodes(RTree::bulk_load(points).root()) * 3 / 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "BULK_LOAD_FILL_FACTOR out of range")]
+    fn test_invalid_bulk_insertion_fill_factor() {
+        let mut tree = RTree::new();
+        tree.insert_bulk_with_fill_factor(vec![[0.0, 0.0]], 101);
+    }
+
+    struct FillFactorParams;
+
+    impl RTreeParams for FillFactorParams {
+        const MIN_SIZE: usize = 2;
+        const MAX_SIZE: usize = 6;
+        const REINSERTION_COUNT: usize = 1;
+        const BULK_LOAD_FILL_FACTOR: u8 = 50;
+        type DefaultInsertionStrategy = RStarInsertionStrategy;
+    }
+
+    #[test]
+    #[should_panic(expected = "BULK_LOAD_FILL_FACTOR out of range")]
+    fn test_invalid_fill_factor() {
+        use crate::RTreeConfig;
+        let config = RTreeConfig {
+            bulk_load_fill_factor: 0,
+            ..Default::default()
+        };
+        let _: RTree<[f64; 2]> = RTree::with_config(config);
+    }
+
+    #[test]
+    #[should_panic(expected = "MIN_SIZE too large")]
+    fn test_invalid_config() {
+        use crate::RTreeConfig;
+        let config = RTreeConfig {
+            min_size: 10,
+            max_size: 12,
+            ..Default::default()
+        };
+        let _: RTree<[f64; 2]> = RTree::with_config(config);
+    }
+
+    #[test]
+    fn test_validate_config() {
+        use crate::RTreeConfig;
+        assert_eq!(RTreeConfig::default().validate(), Ok(()));
+        let invalid = [
+            RTreeConfig {
+                max_size: 3,
+                ..Default::default()
+            },
+            RTreeConfig {
+                min_size: 0,
+                ..Default::default()
+            },
+            RTreeConfig {
+                min_size: 10,
+                max_size: 12,
+                ..Default::default()
+            },
+            RTreeConfig {
+                reinsertion_count: 4,
+                ..Default::default()
+            },
+        ];
+        for config in &invalid {
+            assert!(config.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_check_invariants() {
+        let mut tree = RTree::bulk_load(create_random_points(100, SEED_1));
+        tree.check_invariants();
+        let empty_tree: RTree<[f64; 2]> = RTree::new();
+        empty_tree.check_invariants();
+
+        // Grow the tree by splitting the root node
+        let root = tree.root_mut();
+        let off_split = root.split_off(root.children().len() / 2);
+        let old_root = ::std::mem::replace(root, ParentNode::new_parent(Vec::new()));
+        root.push_child(RTreeNode::Parent(old_root));
+        root.push_child(RTreeNode::Parent(off_split));
+        tree.check_invariants();
+        assert_eq!(tree.iter().count(), 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "envelope differs")]
+    fn test_check_invariants_detects_outdated_envelopes() {
+        let mut tree = RTree::bulk_load(create_random_points(100, SEED_1));
+        for point in tree.iter_mut() {
+            point[0] += 2.0;
+        }
+        tree.check_invariants();
+    }
+
+    #[test]
+    fn test_insert_bulk() {
+        let points = create_random_points(1000, SEED_1);
+        let new_points = create_random_points(200, SEED_2);
+        let mut tree = RTree::new();
+        for point in &points {
+            tree.insert(*point);
+        }
+        tree.insert_bulk(new_points.clone());
+        assert!(tree.root().sanity_check_unsized().is_some());
+        assert_eq!(tree.size(), 1200);
+        for point in points.iter().chain(&new_points) {
+            assert!(tree.contains(point));
+        }
+        for point in &new_points {
+            assert!(tree.remove(point).is_some());
+            tree.insert(*point);
+        }
+        assert_eq!(tree.size(), 1200);
+
+        let mut empty_tree = RTree::new();
+        empty_tree.insert_bulk(new_points.clone());
+        empty_tree.insert_bulk(Vec::new());
+        assert_eq!(empty_tree.size(), 200);
+        assert!(new_points.iter().all(|point| empty_tree.contains(point)));
+    }
+
+    #[test]
+    fn test_merge_and_append() {
+        let points1 = create_random_points(1000, SEED_1);
+        let points2 = create_random_points(80, SEED_2);
+        let tree1 = RTree::bulk_load(points1.clone());
+        let tree2 = RTree::bulk_load(points2.clone());
+        let merged = tree2.merge(tree1);
+        assert!(merged.root().sanity_check_unsized().is_some());
+        assert_eq!(merged.size(), 1080);
+        assert!(points1.iter().chain(&points2).all(|p| merged.contains(p)));
+
+        let mut tree = RTree::new();
+        let mut other = RTree::bulk_load(points2.clone());
+        tree.append(&mut other);
+        assert_eq!(tree.size(), 80);
+        assert_eq!(other.size(), 0);
+        assert_eq!(other.iter().count(), 0);
+        tree.append(&mut other);
+        assert_eq!(tree.size(), 80);
+        assert!(points2.iter().all(|p| tree.contains(p)));
+    }
+
+    fn max_node_size<T: crate::RTreeObject>(node: &ParentNode<T>) -> usize {
+        node.children
+            .iter()
+            .map(|child| match child {
+                RTreeNode::Parent(ref data) => max_node_size(data),
+                RTreeNode::Leaf(_) => 0,
+            })
+            .fold(node.children.len(), usize::max)
+    }
+
+    #[test]
+    fn test_append_with_different_configs() {
+        use crate::RTreeConfig;
+
+        let points1 = create_random_points(500, SEED_1);
+        let points2 = create_random_points(500, SEED_2);
+        let small_config = RTreeConfig::default();
+        let large_config = RTreeConfig {
+            min_size: 8,
+            max_size: 20,
+            ..RTreeConfig::default()
+        };
+        let mut small_tree = RTree::bulk_load_with_config(points1.clone(), small_config);
+        let mut large_tree = RTree::bulk_load_with_config(points2.clone(), large_config);
+        let large_node_size = max_node_size(large_tree.root());
+        assert!(large_node_size > max_node_size(small_tree.root()));
+        small_tree.append(&mut large_tree);
+        assert_eq!(small_tree.config(), &small_config);
+        assert_eq!(small_tree.size(), 1000);
+        assert_eq!(large_tree.size(), 0);
+        // The nodes of the other tree must not be grafted
+        assert!(max_node_size(small_tree.root()) < large_node_size);
+        assert!(small_tree.root().sanity_check_unsized().is_some());
+        assert!(points1
+            .iter()
+            .chain(&points2)
+            .all(|p| small_tree.contains(p)));
+
+        let mut large_tree = RTree::bulk_load_with_config(points2, large_config);
+        large_tree.append(&mut RTree::bulk_load_with_config(points1, small_config));
+        assert_eq!(large_tree.size(), 1000);
+        large_tree.check_invariants();
+    }
+
+    #[test]
+    fn test_split_off_envelope() {
+        use crate::{Envelope, RTreeObject, AABB};
+
+        let rectangles = create_random_rectangles(1000, SEED_1);
+        let envelope = AABB::from_corners([0.2, 0.3], [0.7, 0.6]);
+        let mut contained_tree = RTree::bulk_load(rectangles.clone());
+        let mut intersecting_tree = contained_tree.clone();
+
+        let contained = contained_tree.split_off_envelope(&envelope);
+        let intersecting = intersecting_tree.split_off_envelope_intersecting(&envelope);
+        for tree in &[
+            &contained_tree,
+            &contained,
+            &intersecting_tree,
+            &intersecting,
+        ] {
+            assert!(tree.root().sanity_check_unsized().is_some());
+            assert_eq!(tree.iter().count(), tree.size());
+        }
+        assert_eq!(contained.size() + contained_tree.size(), 1000);
+        assert_eq!(intersecting.size() + intersecting_tree.size(), 1000);
+        assert!(contained.size() > 50);
+        assert!(intersecting.size() > contained.size());
+
+        for rectangle in &rectangles {
+            let is_contained = envelope.contains_envelope(&rectangle.envelope());
+            assert_eq!(contained.contains(rectangle), is_contained);
+            assert_eq!(contained_tree.contains(rectangle), !is_contained);
+            let is_intersecting = envelope.intersects(&rectangle.envelope());
+            assert_eq!(intersecting.contains(rectangle), is_intersecting);
+            assert_eq!(intersecting_tree.contains(rectangle), !is_intersecting);
+        }
+    }
+
     #[test]
     fn test_fmt_debug() {
         let tree = RTree::bulk_load(vec![[0, 1], [0, 1]]);
@@ -0,0 +1,485 @@
+use crate::algorithm::nearest_neighbor::RTreeNodeDistanceWrapper;
+use crate::algorithm::selection_functions::{
+    SelectAtPointFunction, SelectInEnvelopeFunction, SelectionFunction,
+};
+use crate::envelope::Envelope;
+use crate::node::{ParentNode, RTreeNode};
+use crate::object::{PointDistance, RTreeObject};
+use crate::params::{InsertionStrategyKind, RTreeConfig};
+use crate::rtree::RTree;
+use std::collections::BinaryHeap;
+
+/// A query which is used by a [ParameterTuner] to evaluate a candidate configuration.
+///
+/// The queries given to the tuner should resemble the queries of the actual workload.
+pub enum TuningQuery<T>
+where
+    T: RTreeObject,
+{
+    /// Locates all elements containing a point, see [RTree::locate_all_at_point].
+    AtPoint(<T::Envelope as Envelope>::Point),
+    /// Locates all elements contained in an envelope, see [RTree::locate_in_envelope].
+    InEnvelope(T::Envelope),
+    /// Finds the given number of nearest neighbors of a point, see
+    /// [RTree::nearest_neighbor_iter].
+    NearestNeighbors(<T::Envelope as Envelope>::Point, usize),
+}
+
+/// The measured query performance of a single candidate configuration.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TuningResult {
+    /// The evaluated configuration.
+    pub config: RTreeConfig,
+    /// The average number of visited nodes per query.
+    pub node_accesses: f64,
+    /// The average number of child envelopes and elements checked per query.
+    pub entry_checks: f64,
+    /// The estimated cost per query, see [ParameterTuner::node_access_cost].
+    pub cost: f64,
+}
+
+/// Recommends r-tree parameters for a sample of elements and queries.
+///
+/// The tuner builds a tree for every candidate configuration from a sample of elements, either by
+/// bulk loading (the default) or by inserting the elements one by one, see
+/// [ParameterTuner::bulk_load]. The candidates should be built the same way as the actual tree
+/// since both yield different tree structures. The tree is then queried with a representative query mix while counting the visited nodes.
+/// The configuration with the lowest estimated cost per query is recommended.
+///
+/// Counting node accesses alone would always favor the largest nodes, since a tree consisting of
+/// a single root node requires only one node access. The cost of a query is thus estimated as
+/// `node_accesses * node_access_cost + entry_checks`, where `entry_checks` counts every child
+/// node and element whose envelope is inspected.
+///
+/// By default, the candidates consist of several `MAX_SIZE` values between 6 and 64, each
+/// combined with a `MIN_SIZE` of one third and two fifths of `MAX_SIZE` and a
+/// `REINSERTION_COUNT` of zero and 30% of `MAX_SIZE`. Combinations that are not valid according
+/// to [RTreeParams](crate::RTreeParams) are skipped.
+///
+/// # Example
+/// ```
+/// use rstar::{ParameterTuner, RTree, TuningQuery, AABB};
+///
+/// let sample: Vec<_> = (0..1000).map(|i| [(i % 37) as f64, (i % 91) as f64]).collect();
+/// let queries = vec![
+///     TuningQuery::AtPoint([3.0, 4.0]),
+///     TuningQuery::InEnvelope(AABB::from_corners([0.0, 0.0], [10.0, 10.0])),
+///     TuningQuery::NearestNeighbors([20.0, 20.0], 5),
+/// ];
+/// let config = ParameterTuner::new()
+///     .max_sizes(vec![8, 16, 32])
+///     .recommend(&sample, &queries)
+///     .unwrap();
+/// let tree = RTree::bulk_load_with_config(sample, config);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ParameterTuner {
+    max_sizes: Vec<usize>,
+    min_sizes: Option<Vec<usize>>,
+    reinsertion_counts: Option<Vec<usize>>,
+    strategy: InsertionStrategyKind,
+    node_access_cost: f64,
+    bulk_load: bool,
+}
+
+impl Default for ParameterTuner {
+    fn default() -> Self {
+        ParameterTuner {
+            max_sizes: vec![6, 8, 12, 16, 24, 32, 48, 64],
+            min_sizes: None,
+            reinsertion_counts: None,
+            strategy: InsertionStrategyKind::RStar,
+            node_access_cost: 4.0,
+            bulk_load: true,
+        }
+    }
+}
+
+impl ParameterTuner {
+    /// Creates a tuner with the default candidate grid.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the `MAX_SIZE` values to evaluate.
+    pub fn max_sizes(mut self, max_sizes: Vec<usize>) -> Self {
+        self.max_sizes = max_sizes;
+        self
+    }
+
+    /// Sets the `MIN_SIZE` values to evaluate.
+    ///
+    /// Every value is combined with every `MAX_SIZE` value, invalid combinations are skipped.
+    pub fn min_sizes(mut self, min_sizes: Vec<usize>) -> Self {
+        self.min_sizes = Some(min_sizes);
+        self
+    }
+
+    /// Sets the `REINSERTION_COUNT` values to evaluate.
+    ///
+    /// Every value is combined with every `MAX_SIZE` and `MIN_SIZE` value, invalid combinations
+    /// are skipped.
+    pub fn reinsertion_counts(mut self, reinsertion_counts: Vec<usize>) -> Self {
+        self.reinsertion_counts = Some(reinsertion_counts);
+        self
+    }
+
+    /// Sets the insertion strategy used to build the candidate trees.
+    ///
+    /// Defaults to [InsertionStrategyKind::RStar].
+    pub fn strategy(mut self, strategy: InsertionStrategyKind) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Sets whether the candidate trees are bulk loaded or built by inserting the sample.
+    ///
+    /// Defaults to `true`, matching trees created with [RTree::bulk_load_with_config]. Set this
+    /// to `false` if the tree will be filled by [RTree::insert]. Bulk loading ignores the
+    /// `REINSERTION_COUNT` and the insertion strategy, candidates that only differ in these
+    /// values yield the same results.
+    pub fn bulk_load(mut self, bulk_load: bool) -> Self {
+        self.bulk_load = bulk_load;
+        self
+    }
+
+    /// Sets the cost of visiting a node relative to checking a single envelope.
+    ///
+    /// Defaults to `4.0`. Higher values favor larger nodes, e.g. if nodes are loaded from a slow
+    /// storage.
+    pub fn node_access_cost(mut self, node_access_cost: f64) -> Self {
+        self.node_access_cost = node_access_cost;
+        self
+    }
+
+    /// Returns all valid candidate configurations of the grid.
+    pub fn candidates(&self) -> Vec<RTreeConfig> {
+        let mut result = Vec::new();
+        for &max_size in &self.max_sizes {
+            let min_sizes = self
+                .min_sizes
+                .clone()
+                .unwrap_or_else(|| vec![max_size / 3, max_size * 2 / 5]);
+            for &min_size in &min_sizes {
+                let reinsertion_counts = self
+                    .reinsertion_counts
+                    .clone()
+                    .unwrap_or_else(|| vec![0, max_size * 3 / 10]);
+                for &reinsertion_count in &reinsertion_counts {
+                    let config = RTreeConfig {
+                        min_size,
+                        max_size,
+                        reinsertion_count,
+                        strategy: self.strategy,
+                        ..Default::default()
+                    };
+                    if config.validate().is_ok() && !result.contains(&config) {
+                        result.push(config);
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// Evaluates all candidate configurations.
+    ///
+    /// Returns the results sorted by their estimated cost, starting with the cheapest
+    /// configuration. Results whose cost is NaN are left out.
+    pub fn tune<T>(&self, sample: &[T], queries: &[TuningQuery<T>]) -> Vec<TuningResult>
+    where
+        T: PointDistance + Clone,
+    {
+        let num_queries = queries.len().max(1) as f64;
+        let mut results: Vec<_> = self
+            .candidates()
+            .into_iter()
+            .map(|config| {
+                let tree = self.build_tree(sample, config);
+                let mut counter = AccessCounter::default();
+                for query in queries {
+                    counter.count_query(tree.root(), query);
+                }
+                let node_accesses = counter.node_accesses as f64 / num_queries;
+                let entry_checks = counter.entry_checks as f64 / num_queries;
+                TuningResult {
+                    config,
+                    node_accesses,
+                    entry_checks,
+                    cost: node_accesses * self.node_access_cost + entry_checks,
+                }
+            })
+            .collect();
+        // A NaN cost, e.g. caused by a NaN node access cost, can not be ranked
+        results.retain(|result| !result.cost.is_nan());
+        results.sort_by(|l, r| l.cost.partial_cmp(&r.cost).unwrap());
+        results
+    }
+
+    fn build_tree<T>(&self, sample: &[T], config: RTreeConfig) -> RTree<T>
+    where
+        T: PointDistance + Clone,
+    {
+        if self.bulk_load {
+            return RTree::bulk_load_with_config(sample.to_vec(), config);
+        }
+        let mut tree = RTree::with_config(config);
+        for element in sample {
+            tree.insert(element.clone());
+        }
+        tree
+    }
+
+    /// Returns the candidate configuration with the lowest estimated cost.
+    ///
+    /// Returns `None` if the grid contains no valid configuration.
+    pub fn recommend<T>(&self, sample: &[T], queries: &[TuningQuery<T>]) -> Option<RTreeConfig>
+    where
+        T: PointDistance + Clone,
+    {
+        self.tune(sample, queries)
+            .first()
+            .map(|result| result.config)
+    }
+}
+
+#[derive(Default)]
+struct AccessCounter {
+    node_accesses: usize,
+    entry_checks: usize,
+}
+
+impl AccessCounter {
+    fn count_query<T>(&mut self, root: &ParentNode<T>, query: &TuningQuery<T>)
+    where
+        T: PointDistance,
+    {
+        match query {
+            TuningQuery::AtPoint(point) => {
+                self.count_selection(root, &SelectAtPointFunction::new(*point))
+            }
+            TuningQuery::InEnvelope(envelope) => {
+                self.count_selection(root, &SelectInEnvelopeFunction::new(*envelope))
+            }
+            TuningQuery::NearestNeighbors(point, k) => {
+                self.count_nearest_neighbors(root, point, *k)
+            }
+        }
+    }
+
+    fn count_selection<T, Func>(&mut self, node: &ParentNode<T>, func: &Func)
+    where
+        T: RTreeObject,
+        Func: SelectionFunction<T>,
+    {
+        self.node_accesses += 1;
+        for child in &node.children {
+            self.entry_checks += 1;
+            match child {
+                RTreeNode::Parent(ref data) => {
+                    if func.should_unpack_parent(&data.envelope) {
+                        self.count_selection(data, func);
+                    }
+                }
+                RTreeNode::Leaf(ref t) => {
+                    func.should_unpack_leaf(t);
+                }
+            }
+        }
+    }
+
+    fn count_nearest_neighbors<T>(
+        &mut self,
+        root: &ParentNode<T>,
+        point: &<T::Envelope as Envelope>::Point,
+        k: usize,
+    ) where
+        T: PointDistance,
+    {
+        let mut nodes = BinaryHeap::new();
+        let mut remaining = k;
+        self.node_accesses += 1;
+        self.extend_heap(&mut nodes, &root.children, point);
+        while remaining > 0 {
+            match nodes.pop() {
+                Some(RTreeNodeDistanceWrapper {
+                    node: RTreeNode::Parent(ref data),
+                    ..
+                }) => {
+                    self.node_accesses += 1;
+                    self.extend_heap(&mut nodes, &data.children, point);
+                }
+                Some(_) => remaining -= 1,
+                None => break,
+            }
+        }
+    }
+
+    fn extend_heap<'a, T>(
+        &mut self,
+        nodes: &mut BinaryHeap<RTreeNodeDistanceWrapper<'a, T>>,
+        children: &'a [RTreeNode<T>],
+        point: &<T::Envelope as Envelope>::Point,
+    ) where
+        T: PointDistance,
+    {
+        self.entry_checks += children.len();
+        nodes.extend(children.iter().map(|child| {
+            let distance = match child {
+                RTreeNode::Parent(ref data) => data.envelope.distance_2(point),
+                RTreeNode::Leaf(ref t) => t.distance_2(point),
+            };
+            RTreeNodeDistanceWrapper {
+                node: child,
+                distance,
+            }
+        }));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{AccessCounter, ParameterTuner, TuningQuery};
+    use crate::params::{InsertionStrategyKind, RTreeConfig};
+    use crate::test_utilities::*;
+    use crate::{RTree, AABB};
+
+    fn create_queries(num_queries: usize) -> Vec<TuningQuery<[f64; 2]>> {
+        let points = create_random_points(num_queries, SEED_2);
+        points
+            .iter()
+            .enumerate()
+            .map(|(index, point)| match index % 3 {
+                0 => TuningQuery::AtPoint(*point),
+                1 => TuningQuery::InEnvelope(AABB::from_corners(
+                    *point,
+                    [point[0] + 0.1, point[1] + 0.1],
+                )),
+                _ => TuningQuery::NearestNeighbors(*point, 10),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_candidates() {
+        let tuner = ParameterTuner::new()
+            .max_sizes(vec![3, 6, 10])
+            .min_sizes(vec![0, 2, 3, 5])
+            .reinsertion_counts(vec![1, 4])
+            .strategy(InsertionStrategyKind::RRStar);
+        let candidates = tuner.candidates();
+        let expected = [
+            (6, 2, 1),
+            (6, 3, 1),
+            (10, 2, 1),
+            (10, 2, 4),
+            (10, 3, 1),
+            (10, 3, 4),
+            (10, 5, 1),
+            (10, 5, 4),
+        ];
+        assert_eq!(candidates.len(), expected.len());
+        for (config, &(max_size, min_size, reinsertion_count)) in candidates.iter().zip(&expected) {
+            assert_eq!(
+                config,
+                &RTreeConfig {
+                    min_size,
+                    max_size,
+                    reinsertion_count,
+                    strategy: InsertionStrategyKind::RRStar,
+                    ..Default::default()
+                }
+            );
+        }
+        assert!(!ParameterTuner::new().candidates().is_empty());
+    }
+
+    #[test]
+    fn test_tune() {
+        let sample = create_random_points(500, SEED_1);
+        let queries = create_queries(30);
+        let tuner = ParameterTuner::new().max_sizes(vec![6, 16, 32]);
+        let results = tuner.tune(&sample, &queries);
+        assert_eq!(results.len(), tuner.candidates().len());
+        for pair in results.windows(2) {
+            assert!(pair[0].cost <= pair[1].cost);
+        }
+        for result in &results {
+            assert!(result.node_accesses >= 1.0);
+            assert!(result.entry_checks >= result.config.min_size as f64);
+        }
+        assert_eq!(tuner.recommend(&sample, &queries), Some(results[0].config));
+    }
+
+    #[test]
+    fn test_tune_counts_node_accesses() {
+        // All elements fit into the root node
+        let sample = create_random_points(20, SEED_1);
+        let queries = create_queries(9);
+        let results = ParameterTuner::new()
+            .max_sizes(vec![32])
+            .min_sizes(vec![10])
+            .reinsertion_counts(vec![0])
+            .tune(&sample, &queries);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].node_accesses, 1.0);
+        assert_eq!(results[0].entry_checks, 20.0);
+    }
+
+    #[test]
+    fn test_tune_builds_trees_like_the_user() {
+        let sample = create_random_points(500, SEED_1);
+        let queries = create_queries(30);
+        let tuner = ParameterTuner::new()
+            .max_sizes(vec![8])
+            .min_sizes(vec![3])
+            .reinsertion_counts(vec![2]);
+        let config = tuner.candidates()[0];
+        let bulk_loaded = RTree::bulk_load_with_config(sample.clone(), config);
+        let mut inserted = RTree::with_config(config);
+        for point in &sample {
+            inserted.insert(*point);
+        }
+        for &(bulk_load, tree) in &[(true, &bulk_loaded), (false, &inserted)] {
+            let mut counter = AccessCounter::default();
+            for query in &queries {
+                counter.count_query(tree.root(), query);
+            }
+            let results = tuner.clone().bulk_load(bulk_load).tune(&sample, &queries);
+            assert_eq!(
+                results[0].node_accesses,
+                counter.node_accesses as f64 / queries.len() as f64
+            );
+            assert_eq!(
+                results[0].entry_checks,
+                counter.entry_checks as f64 / queries.len() as f64
+            );
+        }
+    }
+
+    #[test]
+    fn test_recommend_without_candidates() {
+        let sample = create_random_points(20, SEED_1);
+        let tuner = ParameterTuner::new().max_sizes(vec![2]);
+        assert_eq!(tuner.recommend(&sample, &create_queries(3)), None);
+    }
+
+    #[test]
+    fn test_tune_skips_nan_costs() {
+        let sample = create_random_points(20, SEED_1);
+        let tuner = ParameterTuner::new().node_access_cost(f64::NAN);
+        assert!(tuner.tune(&sample, &create_queries(3)).is_empty());
+        assert_eq!(tuner.recommend(&sample, &create_queries(3)), None);
+    }
+
+    #[test]
+    fn test_node_access_cost_favors_larger_nodes() {
+        let sample = create_random_points(500, SEED_1);
+        let queries = create_queries(30);
+        let tuner = ParameterTuner::new().max_sizes(vec![6, 64]);
+        let cheap = tuner.clone().node_access_cost(0.0);
+        let expensive = tuner.node_access_cost(1000.0);
+        assert_eq!(cheap.recommend(&sample, &queries).unwrap().max_size, 6);
+        assert_eq!(expensive.recommend(&sample, &queries).unwrap().max_size, 64);
+    }
+}